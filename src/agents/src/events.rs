@@ -0,0 +1,114 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc::{self, UnboundedReceiver};
+use futures::channel::oneshot;
+use futures::Stream;
+use uuid::Uuid;
+
+use buttercup_bts::events::BTNodeExecutionEvent;
+use buttercup_bts::events::sink::BTNodeExecutionEventSink;
+use buttercup_bts::node::decorator::reactive::DataChangeHandlingError;
+
+///
+/// Stream of JSON serialized execution events of a single agent. The underlying listener is
+/// detached from the agent's event sink once the stream is dropped, e.g. when a client
+/// disconnects.
+///
+pub struct AgentEventStream {
+
+    listener_id: Uuid,
+    receiver: UnboundedReceiver<String>,
+    sink: Arc<BTNodeExecutionEventSink>
+
+}
+
+impl AgentEventStream {
+
+    pub fn subscribe(sink: Arc<BTNodeExecutionEventSink>) -> AgentEventStream {
+        let (sender, receiver) = mpsc::unbounded();
+
+        let listener_id = sink.add_listener(
+            Arc::new(move |event| {
+                if let Ok(serialized) = serde_json::to_string(event) {
+                    let _ = sender.unbounded_send(serialized);
+                }
+            }));
+
+        AgentEventStream {
+            listener_id,
+            receiver,
+            sink
+        }
+    }
+
+}
+
+impl Stream for AgentEventStream {
+    type Item = String;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl Drop for AgentEventStream {
+    fn drop(&mut self) {
+        self.sink.remove_listener(&self.listener_id);
+    }
+}
+
+///
+/// Resolves with the first reactive error reported by the agent's context, the listener is
+/// detached once the watch is dropped.
+///
+pub struct ReactiveErrorWatch {
+
+    listener_id: Uuid,
+    receiver: oneshot::Receiver<DataChangeHandlingError>,
+    sink: Arc<BTNodeExecutionEventSink>
+
+}
+
+impl ReactiveErrorWatch {
+
+    pub fn subscribe(sink: Arc<BTNodeExecutionEventSink>) -> ReactiveErrorWatch {
+        let (sender, receiver) = oneshot::channel();
+        let sender = Mutex::new(Some(sender));
+
+        let listener_id = sink.add_listener(
+            Arc::new(move |event| {
+                if let BTNodeExecutionEvent::ReactiveErrorOccurred(event) = event {
+                    if let Some(sender) = sender.lock().ok().and_then(|mut sender| sender.take()) {
+                        let _ = sender.send(event.get_error().clone());
+                    }
+                }
+            }));
+
+        ReactiveErrorWatch {
+            listener_id,
+            receiver,
+            sink
+        }
+    }
+
+}
+
+impl Future for ReactiveErrorWatch {
+    type Output = DataChangeHandlingError;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.receiver).poll(cx) {
+            Poll::Ready(Ok(err)) => Poll::Ready(err),
+            _ => Poll::Pending
+        }
+    }
+}
+
+impl Drop for ReactiveErrorWatch {
+    fn drop(&mut self) {
+        self.sink.remove_listener(&self.listener_id);
+    }
+}
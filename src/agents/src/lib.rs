@@ -1,23 +1,263 @@
+use std::collections::{HashMap, VecDeque};
 use std::future::Future;
-use std::sync::{Arc, Mutex, PoisonError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard};
+use std::time::Duration;
 
 use actix::{Actor, Context, Handler, ResponseActFuture};
-use chrono::{DateTime, NaiveDateTime, Utc};
-use futures::future::{Abortable, Aborted, AbortHandle, AbortRegistration};
+use chrono::NaiveDateTime;
+use futures::future::{self, Abortable, Aborted, AbortHandle, AbortRegistration, Either};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 use buttercup_bts::context::{BTNodeExecutionContext, BTNodeExecutionContextHolder};
+use buttercup_bts::node::decorator::reactive::DataChangeHandlingError;
 use buttercup_bts::tick::{TickError, TickStatus};
 use buttercup_bts::tree::BehaviorTree;
 
+use crate::events::ReactiveErrorWatch;
+use crate::supervision::{KEPT_RESTARTS_COUNT, RestartPolicy, RestartRecord};
+
+pub mod events;
+pub mod listing;
+pub mod scheduling;
 pub mod service;
+pub mod supervision;
+
+///
+/// Number of the most recent execution results kept per agent.
+///
+pub const KEPT_RESULTS_COUNT: usize = 10;
 
 pub struct Agent {
 
     id: Uuid,
     context: Arc<BTNodeExecutionContextHolder>,
-    tree: Arc<BehaviorTree>
+    created_at_utc: NaiveDateTime,
+    definition: RwLock<AgentDefinition>,
+    run: Mutex<AgentRun>,
+    tree: RwLock<Arc<BehaviorTree>>
+
+}
+
+///
+/// Everything an agent is built from, apart from its context. Arguments are
+/// the initial values of the blackboard, validated against the ones declared by the tree.
+/// The agent is pinned to the given version of the tree, the latest one if none is given.
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentDefinition {
+
+    arguments: Option<Value>,
+    labels: HashMap<String, String>,
+    reactive_error_policy: ReactiveErrorPolicy,
+    restart_policy: RestartPolicy,
+    tree_id: i32,
+
+    #[serde(default)]
+    tree_version: Option<u32>
+
+}
+
+impl AgentDefinition {
+
+    pub fn new(arguments: Option<Value>,
+               labels: HashMap<String, String>,
+               reactive_error_policy: ReactiveErrorPolicy,
+               restart_policy: RestartPolicy,
+               tree_id: i32,
+               tree_version: Option<u32>) -> AgentDefinition {
+        AgentDefinition {
+            arguments,
+            labels,
+            reactive_error_policy,
+            restart_policy,
+            tree_id,
+            tree_version
+        }
+    }
+
+    pub fn get_arguments(&self) -> &Option<Value> {
+        &self.arguments
+    }
+
+    pub fn get_labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    pub fn get_reactive_error_policy(&self) -> &ReactiveErrorPolicy {
+        &self.reactive_error_policy
+    }
+
+    pub fn get_restart_policy(&self) -> &RestartPolicy {
+        &self.restart_policy
+    }
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_tree_version(&self) -> &Option<u32> {
+        &self.tree_version
+    }
+
+}
+
+impl From<i32> for AgentDefinition {
+    fn from(tree_id: i32) -> Self {
+        AgentDefinition::new(
+            None, HashMap::new(), ReactiveErrorPolicy::default(), RestartPolicy::default(), tree_id, None)
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone, Copy)]
+pub enum AgentState {
+
+    Created,
+    Restarting,
+    Running,
+    Succeeded,
+    Failed,
+    Errored,
+    Stopped
+
+}
+
+impl AgentState {
+
+    fn from_result(result: &Result<TickStatus, AgentError>) -> AgentState {
+        match result {
+            Ok(TickStatus::Success) => AgentState::Succeeded,
+            Ok(TickStatus::Failure) => AgentState::Failed,
+            Err(AgentError::AbortedError(_)) => AgentState::Stopped,
+            Err(_) => AgentState::Errored
+        }
+    }
+
+}
+
+struct AgentRun {
+
+    abort_handle: Option<AbortHandle>,
+    closed: bool,
+    id: Option<Uuid>,
+    restarts: VecDeque<RestartRecord>,
+    restarts_count: u32,
+    results: VecDeque<AgentExecutionResult>,
+    started_at_utc: Option<NaiveDateTime>,
+    state: AgentState,
+    task: Option<JoinHandle<()>>
+
+}
+
+///
+/// Snapshot of the state of an agent, along with its most recent execution results.
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentStatus {
+
+    id: Uuid,
+    restarts: Vec<RestartRecord>,
+    results: Vec<AgentExecutionResult>,
+    state: AgentState,
+    tree_id: i32,
+    tree_version: u32
+
+}
+
+///
+/// Short description of an agent, as returned when listing agents.
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentSummary {
+
+    id: Uuid,
+    created_at_utc: NaiveDateTime,
+    labels: HashMap<String, String>,
+    last_result: Option<AgentExecutionResult>,
+    started_at_utc: Option<NaiveDateTime>,
+    state: AgentState,
+    tree_id: i32,
+    tree_version: u32
+
+}
+
+impl AgentStatus {
+
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_restarts(&self) -> &Vec<RestartRecord> {
+        &self.restarts
+    }
+
+    pub fn get_results(&self) -> &Vec<AgentExecutionResult> {
+        &self.results
+    }
+
+    pub fn get_state(&self) -> &AgentState {
+        &self.state
+    }
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_tree_version(&self) -> &u32 {
+        &self.tree_version
+    }
+
+}
+
+impl AgentSummary {
+
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_created_at_utc(&self) -> &NaiveDateTime {
+        &self.created_at_utc
+    }
+
+    pub fn get_labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+
+    pub fn get_last_result(&self) -> &Option<AgentExecutionResult> {
+        &self.last_result
+    }
+
+    pub fn get_started_at_utc(&self) -> &Option<NaiveDateTime> {
+        &self.started_at_utc
+    }
+
+    pub fn get_state(&self) -> &AgentState {
+        &self.state
+    }
+
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_tree_version(&self) -> &u32 {
+        &self.tree_version
+    }
+
+}
+
+///
+/// Decides what happens to a running agent once one of its reactive conditions fails
+/// to handle a change of values. Errors are reported to the event sink and metrics either way.
+///
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone, Copy, Default)]
+pub enum ReactiveErrorPolicy {
+
+    AbortAgent,
+    #[default]
+    Continue
 
 }
 
@@ -25,37 +265,333 @@ impl Agent {
 
     pub fn new(id: Uuid,
                context: Arc<BTNodeExecutionContextHolder>,
+               definition: AgentDefinition,
                tree: Arc<BehaviorTree>) -> Agent {
         Agent {
             id,
+            created_at_utc: context.get_context().get_clock().now(),
             context,
-            tree
+            definition: RwLock::new(definition),
+            run: Mutex::new(
+                AgentRun {
+                    abort_handle: None,
+                    closed: false,
+                    id: None,
+                    restarts: VecDeque::with_capacity(KEPT_RESTARTS_COUNT),
+                    restarts_count: 0,
+                    results: VecDeque::with_capacity(KEPT_RESULTS_COUNT),
+                    started_at_utc: None,
+                    state: AgentState::Created,
+                    task: None
+                }),
+            tree: RwLock::new(tree)
+        }
+    }
+
+    pub fn get_context(&self) -> &Arc<BTNodeExecutionContextHolder> {
+        &self.context
+    }
+
+    pub fn get_tree(&self) -> Arc<BehaviorTree> {
+        self.tree.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    pub fn get_definition(&self) -> AgentDefinition {
+        self.read_definition().clone()
+    }
+
+    pub fn get_state(&self) -> AgentState {
+        self.lock_run().state
+    }
+
+    pub fn get_status(&self) -> AgentStatus {
+        let tree = self.get_tree();
+        let run = self.lock_run();
+
+        AgentStatus {
+            id: self.id,
+            restarts: run.restarts.iter().cloned().collect(),
+            results: run.results.iter().cloned().collect(),
+            state: run.state,
+            tree_id: *tree.get_id(),
+            tree_version: *tree.get_version()
+        }
+    }
+
+    pub fn get_summary(&self) -> AgentSummary {
+        let tree = self.get_tree();
+        let run = self.lock_run();
+
+        AgentSummary {
+            id: self.id,
+            created_at_utc: self.created_at_utc,
+            labels: self.read_definition().labels.clone(),
+            last_result: run.results.back().cloned(),
+            started_at_utc: run.started_at_utc,
+            state: run.state,
+            tree_id: *tree.get_id(),
+            tree_version: *tree.get_version()
         }
     }
 
+    ///
+    /// Moves the agent to running, the returned id has to be passed when the run finishes.
+    ///
+    pub fn begin_run(&self) -> Result<(Uuid, AbortRegistration), AgentError> {
+        Agent::do_begin_run(&mut self.lock_run(), self.now())
+    }
+
+    ///
+    /// Begins a run and executes it on the given runtime, keeping its task so that
+    /// closing the agent can wait for it to end.
+    ///
+    pub fn spawn_run(self: &Arc<Self>,
+                     handle: &Handle) -> Result<(), AgentError> {
+        let mut run = self.lock_run();
+
+        let (run_id, abort_registration) = Agent::do_begin_run(&mut run, self.now())?;
+        let agent = self.clone();
+
+        run.task = Some(handle.spawn(async move {
+            agent.run(run_id, abort_registration).await
+        }));
+
+        Result::Ok(())
+    }
+
+    fn do_begin_run(run: &mut AgentRun,
+                    now: NaiveDateTime) -> Result<(Uuid, AbortRegistration), AgentError> {
+        if run.closed {
+            return Result::Err(AgentError::Closed);
+        }
+
+        if let AgentState::Running | AgentState::Restarting = run.state {
+            return Result::Err(AgentError::AlreadyRunning);
+        }
+
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let run_id = Uuid::new_v4();
+
+        run.abort_handle = Some(abort_handle);
+        run.id = Some(run_id);
+        run.restarts_count = 0;
+        run.started_at_utc = Some(now);
+        run.state = AgentState::Running;
+
+        Result::Ok((run_id, abort_registration))
+    }
+
+    ///
+    /// Executes the run begun before, restarting the agent for as long as its restart
+    /// policy allows it and the agent is not stopped.
+    ///
+    pub async fn run(&self,
+                     run_id: Uuid,
+                     abort_registration: AbortRegistration) {
+        let mut abort_registration = abort_registration;
+
+        loop {
+            let result = self.start(abort_registration).await;
+
+            let (backoff, backoff_registration) = match self.finish_run(&run_id, result) {
+                None => return,
+                Some(restart) => restart
+            };
+
+            if Abortable::new(self.context.get_context().sleep(backoff), backoff_registration)
+                .await
+                .is_err() {
+                return;
+            }
+
+            abort_registration = match self.restart_run(&run_id, backoff) {
+                None => return,
+                Some(abort_registration) => abort_registration
+            };
+        }
+    }
+
+    ///
+    /// Keeps the result and, unless the agent was stopped or started again in the meantime,
+    /// moves it to the state matching the result. If the agent is to be restarted, returns
+    /// the backoff to wait for, which can be aborted by stopping the agent.
+    ///
+    pub fn finish_run(&self,
+                      run_id: &Uuid,
+                      result: AgentExecutionResult) -> Option<(Duration, AbortRegistration)> {
+        let mut run = self.lock_run();
+
+        let mut restart = None;
+
+        if run.id.as_ref() == Some(run_id) {
+            let backoff = self.read_definition()
+                .restart_policy
+                .get_backoff(&result.result, run.restarts_count);
+
+            match backoff {
+                None => {
+                    run.abort_handle = None;
+                    run.id = None;
+                    run.state = AgentState::from_result(&result.result);
+                },
+                Some(backoff) => {
+                    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+
+                    run.abort_handle = Some(abort_handle);
+                    run.state = AgentState::Restarting;
+
+                    restart = Some((backoff, abort_registration));
+                }
+            }
+        }
+
+        if run.results.len() == KEPT_RESULTS_COUNT {
+            run.results.pop_front();
+        }
+
+        run.results.push_back(result);
+
+        restart
+    }
+
+    fn restart_run(&self,
+                   run_id: &Uuid,
+                   backoff: Duration) -> Option<AbortRegistration> {
+        let mut run = self.lock_run();
+
+        if run.id.as_ref() != Some(run_id) {
+            return None;
+        }
+
+        let previous_result_id = run.results.back().map(|result| result.id)?;
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+
+        run.restarts_count += 1;
+
+        if run.restarts.len() == KEPT_RESTARTS_COUNT {
+            run.restarts.pop_front();
+        }
+
+        let record = RestartRecord::new(run.restarts_count,
+                                        backoff.as_millis() as u64,
+                                        previous_result_id,
+                                        self.now());
+
+        run.restarts.push_back(record);
+        run.abort_handle = Some(abort_handle);
+        run.state = AgentState::Running;
+
+        Some(abort_registration)
+    }
+
+    ///
+    /// Returns false if the agent was not running.
+    ///
+    pub fn stop(&self) -> bool {
+        let mut run = self.lock_run();
+
+        match run.abort_handle.take() {
+            None => false,
+            Some(abort_handle) => {
+                abort_handle.abort();
+
+                run.id = None;
+                run.state = AgentState::Stopped;
+
+                true
+            }
+        }
+    }
+
+    ///
+    /// Stops the agent for good, no run can be begun afterwards. Returns the task
+    /// of the last spawned run, if any, to wait for.
+    ///
+    pub fn close(&self) -> Option<JoinHandle<()>> {
+        let mut run = self.lock_run();
+
+        run.closed = true;
+
+        if let Some(abort_handle) = run.abort_handle.take() {
+            abort_handle.abort();
+
+            run.id = None;
+            run.state = AgentState::Stopped;
+        }
+
+        run.task.take()
+    }
+
+    ///
+    /// Moves the agent, unless it is running or restarting, to another tree,
+    /// keeping its context along with the blackboard. The definition is pinned
+    /// to the version of the tree.
+    ///
+    pub fn migrate(&self,
+                   tree: Arc<BehaviorTree>) -> Result<(), AgentError> {
+        let run = self.lock_run();
+
+        if let AgentState::Running | AgentState::Restarting = run.state {
+            return Result::Err(AgentError::AlreadyRunning);
+        }
+
+        self.definition.write().unwrap_or_else(PoisonError::into_inner).tree_version =
+            Some(*tree.get_version());
+        *self.tree.write().unwrap_or_else(PoisonError::into_inner) = tree;
+
+        Result::Ok(())
+    }
+
+    ///
+    /// Reads the clock of the context directly, bookkeeping of the agent is not part
+    /// of the execution recording.
+    ///
+    pub(crate) fn now(&self) -> NaiveDateTime {
+        self.context.get_context().get_clock().now()
+    }
+
+    fn read_definition(&self) -> RwLockReadGuard<'_, AgentDefinition> {
+        self.definition.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock_run(&self) -> MutexGuard<'_, AgentRun> {
+        self.run.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     pub async fn start(&self,
                        abort_registration: AbortRegistration) -> AgentExecutionResult {
         let exec_id = Uuid::new_v4();
-        let started_at = Utc::now();
+        let started_at = self.context.get_context().now();
 
         let result = self.do_start(abort_registration).await;
 
         AgentExecutionResult::new(
             exec_id,
             self.id.clone(),
-            Utc::now().naive_utc(),
+            self.context.get_context().now(),
             result,
-            started_at.naive_utc())
+            started_at)
     }
 
     async fn do_start(&self,
                       abort_registration: AbortRegistration) -> Result<TickStatus, AgentError> {
-        Result::Ok(
-            Abortable::new(
-                self.tree.tick(
-                    Uuid::new_v4(),
-                    self.context.get_context()), abort_registration)
-                .await??)
+        let context = self.context.get_context();
+        let tree = self.get_tree();
+        let tick = Abortable::new(tree.tick(Uuid::new_v4(), context), abort_registration);
+
+        let reactive_error_policy = self.read_definition().reactive_error_policy;
+
+        match reactive_error_policy {
+            ReactiveErrorPolicy::AbortAgent => {
+                let reactive_error = ReactiveErrorWatch::subscribe(context.get_event_sink().clone());
+
+                match future::select(Box::pin(tick), reactive_error).await {
+                    Either::Left((result, _)) => Result::Ok(result??),
+                    Either::Right((err, _)) => Result::Err(AgentError::ReactiveError(err))
+                }
+            },
+            ReactiveErrorPolicy::Continue => Result::Ok(tick.await??)
+        }
     }
 
 }
@@ -65,7 +601,9 @@ pub enum AgentError {
 
     AbortedError(String),
     AlreadyRunning,
-    ExecutionError(TickError)
+    Closed,
+    ExecutionError(TickError),
+    ReactiveError(DataChangeHandlingError)
 
 }
 
@@ -75,12 +613,19 @@ impl From<TickError> for AgentError {
     }
 }
 
+impl From<DataChangeHandlingError> for AgentError {
+    fn from(val: DataChangeHandlingError) -> Self {
+        AgentError::ReactiveError(val)
+    }
+}
+
 impl From<Aborted> for AgentError {
     fn from(val: Aborted) -> Self {
         AgentError::AbortedError(val.to_string())
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AgentExecutionResult {
 
     id: Uuid,
@@ -107,6 +652,14 @@ impl AgentExecutionResult {
         }
     }
 
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_result(&self) -> &Result<TickStatus, AgentError> {
+        &self.result
+    }
+
 }
 
 
@@ -115,14 +668,23 @@ impl AgentExecutionResult {
 mod tests {
     use std::sync::Arc;
 
+    use std::time::Duration;
+
     use actix_rt::System;
     use dashmap::DashMap;
+    use futures::FutureExt;
+    use futures::future::AbortHandle;
 
     use buttercup_blackboards::LocalBlackboard;
+    use buttercup_blackboards::LocalBlackboardError;
     use buttercup_bts::context::{BTNodeContextService, BTNodeExecutionContext, test_utils};
     use buttercup_bts::node::action::logging::PrintLogActionNode;
+    use buttercup_bts::node::action::wait::WaitDurationActionNode;
+    use buttercup_bts::node::decorator::invert::InvertDecoratorNode;
     use buttercup_bts::node::root::one_off::OneOffRootBTNode;
 
+    use crate::supervision::RestartMode;
+
     use super::*;
 
     #[actix_rt::test]
@@ -133,6 +695,7 @@ mod tests {
 
             let mut agent = Agent::new(Uuid::new_v4(),
                                        context.clone(),
+                                       1.into(),
                                        Arc::new(
                                            BehaviorTree::new(1,
                                                              OneOffRootBTNode::new(
@@ -158,4 +721,142 @@ mod tests {
         test_utils::destroy(path);
     }
 
+    #[actix_rt::test]
+    async fn test_moves_through_states_keeping_results() {
+        let path = {
+            let context: Arc<BTNodeExecutionContextHolder> =
+                Arc::new(BTNodeContextService::default().build_new().unwrap());
+
+            let agent = Agent::new(
+                Uuid::new_v4(),
+                context.clone(),
+                1.into(),
+                Arc::new(
+                    BehaviorTree::new(
+                        1,
+                        OneOffRootBTNode::new(
+                            1,
+                            PrintLogActionNode::new(2, "hello".to_owned()).into()).into())));
+
+            assert_eq!(AgentState::Created, agent.get_state());
+
+            for _ in 0..KEPT_RESULTS_COUNT + 1 {
+                let (run_id, abort_registration) = agent.begin_run().unwrap();
+
+                assert_eq!(AgentState::Running, agent.get_state());
+                assert_eq!(Some(AgentError::AlreadyRunning), agent.begin_run().err());
+
+                let result = agent.start(abort_registration).await;
+                agent.finish_run(&run_id, result);
+            }
+
+            let status = agent.get_status();
+
+            assert_eq!(AgentState::Succeeded, *status.get_state());
+            assert_eq!(KEPT_RESULTS_COUNT, status.get_results().len());
+            assert!(!agent.stop());
+
+            let (run_id, abort_registration) = agent.begin_run().unwrap();
+
+            assert!(agent.stop());
+
+            let result = agent.start(abort_registration).await;
+            agent.finish_run(&run_id, result);
+
+            assert_eq!(AgentState::Stopped, agent.get_state());
+
+            test_utils::get_path(context.get_context())
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_restarts_failing_agent_up_to_max_restarts() {
+        let path = {
+            let context: Arc<BTNodeExecutionContextHolder> =
+                Arc::new(BTNodeContextService::default().build_new().unwrap());
+
+            let agent = Agent::new(
+                Uuid::new_v4(),
+                context.clone(),
+                AgentDefinition::new(
+                    None,
+                    HashMap::new(),
+                    ReactiveErrorPolicy::default(),
+                    RestartPolicy::new(1, 2, Some(2), RestartMode::OnFailure),
+                    1,
+                    None),
+                Arc::new(
+                    BehaviorTree::new(
+                        1,
+                        OneOffRootBTNode::new(
+                            1,
+                            InvertDecoratorNode::new(
+                                2,
+                                Box::new(
+                                    PrintLogActionNode::new(3, "failing".to_owned()).into()))
+                                .into()).into())));
+
+            let (run_id, abort_registration) = agent.begin_run().unwrap();
+
+            agent.run(run_id, abort_registration).await;
+
+            let status = agent.get_status();
+
+            assert_eq!(AgentState::Failed, *status.get_state());
+            assert_eq!(3, status.get_results().len());
+            assert_eq!(vec![1, 2],
+                       status.get_restarts()
+                           .iter()
+                           .map(|restart| *restart.get_attempt())
+                           .collect::<Vec<u32>>());
+            assert_eq!(status.get_results()[1].get_id(),
+                       status.get_restarts()[1].get_previous_result_id());
+
+            test_utils::get_path(context.get_context())
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_aborts_on_reactive_error_when_configured() {
+        let path = {
+            let context: Arc<BTNodeExecutionContextHolder> =
+                Arc::new(BTNodeContextService::default().build_new().unwrap());
+
+            let agent = Agent::new(
+                Uuid::new_v4(),
+                context.clone(),
+                AgentDefinition::new(
+                    None, HashMap::new(), ReactiveErrorPolicy::AbortAgent, RestartPolicy::default(), 1, None),
+                Arc::new(
+                    BehaviorTree::new(
+                        1,
+                        OneOffRootBTNode::new(
+                            1,
+                            WaitDurationActionNode::new(
+                                2, Duration::from_secs(3600).into()).into()).into())));
+
+            let (_, abort_registration) = AbortHandle::new_pair();
+            let mut start = agent.start(abort_registration).boxed();
+
+            assert!((&mut start).now_or_never().is_none());
+
+            let err = DataChangeHandlingError::BlackboardError(
+                3, LocalBlackboardError::DbError("Broken.".to_owned()));
+
+            context.get_context().report_reactive_error(&err);
+
+            assert_eq!(Result::Err(AgentError::ReactiveError(err)), start.await.result);
+            assert_eq!(1, context.get_context().get_metrics().get_reactive_errors_count());
+            assert!(!context.get_context().get_event_sink().has_listeners());
+
+            test_utils::get_path(context.get_context())
+        };
+
+        test_utils::destroy(path);
+    }
+
 }
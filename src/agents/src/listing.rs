@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{AgentState, AgentSummary};
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 500;
+
+///
+/// Criteria the listed agents have to meet, the ones left empty match every agent.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AgentFilter {
+
+    created_after_utc: Option<NaiveDateTime>,
+    created_before_utc: Option<NaiveDateTime>,
+    labels: HashMap<String, String>,
+    state: Option<AgentState>,
+    tree_id: Option<i32>
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub struct Pagination {
+
+    offset: usize,
+    limit: usize
+
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentsPage {
+
+    agents: Vec<AgentSummary>,
+    offset: usize,
+    total: usize
+
+}
+
+impl AgentFilter {
+
+    pub fn new(created_after_utc: Option<NaiveDateTime>,
+               created_before_utc: Option<NaiveDateTime>,
+               labels: HashMap<String, String>,
+               state: Option<AgentState>,
+               tree_id: Option<i32>) -> AgentFilter {
+        AgentFilter {
+            created_after_utc,
+            created_before_utc,
+            labels,
+            state,
+            tree_id
+        }
+    }
+
+    ///
+    /// Creation time range is inclusive on both ends.
+    ///
+    pub fn matches(&self,
+                   summary: &AgentSummary) -> bool {
+        self.tree_id.is_none_or(|tree_id| tree_id == *summary.get_tree_id())
+            && self.state.is_none_or(|state| state == *summary.get_state())
+            && self.created_after_utc
+                .is_none_or(|after| *summary.get_created_at_utc() >= after)
+            && self.created_before_utc
+                .is_none_or(|before| *summary.get_created_at_utc() <= before)
+            && self.labels
+                .iter()
+                .all(|(key, value)| summary.get_labels().get(key) == Some(value))
+    }
+
+}
+
+impl Pagination {
+
+    ///
+    /// Limit is capped at the maximum page size.
+    ///
+    pub fn new(offset: usize,
+               limit: usize) -> Pagination {
+        Pagination {
+            offset,
+            limit: limit.min(MAX_PAGE_SIZE)
+        }
+    }
+
+    pub fn get_offset(&self) -> &usize {
+        &self.offset
+    }
+
+    pub fn get_limit(&self) -> &usize {
+        &self.limit
+    }
+
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Pagination::new(0, DEFAULT_PAGE_SIZE)
+    }
+}
+
+impl AgentsPage {
+
+    ///
+    /// Orders the summaries by creation time and id, so that consecutive pages do not overlap.
+    ///
+    pub fn new(mut summaries: Vec<AgentSummary>,
+               pagination: &Pagination) -> AgentsPage {
+        summaries.sort_by(|first, second|
+            first.get_created_at_utc()
+                .cmp(second.get_created_at_utc())
+                .then_with(|| first.get_id().cmp(second.get_id())));
+
+        let total = summaries.len();
+
+        AgentsPage {
+            agents: summaries
+                .into_iter()
+                .skip(pagination.offset)
+                .take(pagination.limit)
+                .collect(),
+            offset: pagination.offset,
+            total
+        }
+    }
+
+    pub fn get_agents(&self) -> &Vec<AgentSummary> {
+        &self.agents
+    }
+
+    pub fn get_offset(&self) -> &usize {
+        &self.offset
+    }
+
+    pub fn get_total(&self) -> &usize {
+        &self.total
+    }
+
+}
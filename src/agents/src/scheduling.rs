@@ -0,0 +1,258 @@
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use buttercup_values::wrappers::{TzWrapper, Wrapper};
+use buttercup_values::zoned_date_time::ZonedDateTime;
+
+use crate::AgentDefinition;
+
+///
+/// When the agent is started, either on each occurrence of a cron expression evaluated
+/// in the given time zone, or once at the given time. Cron expressions are given
+/// with five fields, or six when starting with seconds.
+///
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, Debug, Clone)]
+pub enum ScheduleDefinition {
+
+    At(ZonedDateTime),
+    Cron(String, TzWrapper)
+
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Schedule {
+
+    id: Uuid,
+    agent_id: Uuid,
+    definition: ScheduleDefinition
+
+}
+
+///
+/// Schedule along with the definition and the blackboard of its agent, so that the agent
+/// can be rebuilt with its values once the process restarts.
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PersistedSchedule {
+
+    agent: AgentDefinition,
+    blackboard_id: Uuid,
+    schedule: Schedule
+
+}
+
+///
+/// Keeps the schedules in a JSON file, if given a path.
+///
+#[derive(Default)]
+pub struct ScheduleStore {
+
+    path: Option<PathBuf>,
+    lock: Mutex<()>
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum SchedulingError {
+
+    InvalidCronExpression(String),
+    IOError(String),
+    NonExistentLocalTime(NaiveDateTime),
+    SerializationError(String),
+    StartInPast
+
+}
+
+impl ScheduleDefinition {
+
+    ///
+    /// The schedule has to start at least once after the given time.
+    ///
+    pub fn validate(&self,
+                    now_utc: &NaiveDateTime) -> Result<(), SchedulingError> {
+        self.get_next_start(now_utc)?
+            .map(|_| ())
+            .ok_or(SchedulingError::StartInPast)
+    }
+
+    ///
+    /// Returns the first start, in UTC, strictly after the given time.
+    ///
+    pub fn get_next_start(&self,
+                          after_utc: &NaiveDateTime) -> Result<Option<NaiveDateTime>, SchedulingError> {
+        match self {
+            ScheduleDefinition::At(at) => {
+                let start = at.get_zone()
+                    .from_local_datetime(at.get_date_time())
+                    .earliest()
+                    .ok_or_else(|| SchedulingError::NonExistentLocalTime(*at.get_date_time()))?
+                    .naive_utc();
+
+                Result::Ok(Some(start).filter(|start| start > after_utc))
+            },
+            ScheduleDefinition::Cron(expression, zone) => {
+                let schedule = parse_cron(expression)?;
+                let after = Utc.from_utc_datetime(after_utc).with_timezone(zone.get());
+
+                Result::Ok(schedule.after(&after).next().map(|start| start.naive_utc()))
+            }
+        }
+    }
+
+}
+
+fn parse_cron(expression: &str) -> Result<cron::Schedule, SchedulingError> {
+    let expression = match expression.split_whitespace().count() {
+        5 => format!("0 {}", expression),
+        _ => expression.to_owned()
+    };
+
+    cron::Schedule::from_str(&expression)
+        .map_err(|err| SchedulingError::InvalidCronExpression(err.to_string()))
+}
+
+impl Schedule {
+
+    pub fn new(id: Uuid,
+               agent_id: Uuid,
+               definition: ScheduleDefinition) -> Schedule {
+        Schedule {
+            id,
+            agent_id,
+            definition
+        }
+    }
+
+    pub fn get_id(&self) -> &Uuid {
+        &self.id
+    }
+
+    pub fn get_agent_id(&self) -> &Uuid {
+        &self.agent_id
+    }
+
+    pub fn get_definition(&self) -> &ScheduleDefinition {
+        &self.definition
+    }
+
+}
+
+impl PersistedSchedule {
+
+    pub fn new(agent: AgentDefinition,
+               blackboard_id: Uuid,
+               schedule: Schedule) -> PersistedSchedule {
+        PersistedSchedule {
+            agent,
+            blackboard_id,
+            schedule
+        }
+    }
+
+    pub fn get_agent(&self) -> &AgentDefinition {
+        &self.agent
+    }
+
+    pub fn get_blackboard_id(&self) -> &Uuid {
+        &self.blackboard_id
+    }
+
+    pub fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+}
+
+impl ScheduleStore {
+
+    pub fn new(path: PathBuf) -> ScheduleStore {
+        ScheduleStore {
+            path: Some(path),
+            lock: Mutex::new(())
+        }
+    }
+
+    ///
+    /// Returns no schedules if the file does not exist yet.
+    ///
+    pub fn load(&self) -> Result<Vec<PersistedSchedule>, SchedulingError> {
+        let _lock = self.lock.lock().unwrap();
+
+        match &self.path {
+            Some(path) if path.exists() => {
+                let content = fs::read_to_string(path)?;
+
+                serde_json::from_str(&content)
+                    .map_err(|err| SchedulingError::SerializationError(err.to_string()))
+            },
+            _ => Result::Ok(Vec::new())
+        }
+    }
+
+    pub fn save(&self,
+                schedules: &[PersistedSchedule]) -> Result<(), SchedulingError> {
+        let _lock = self.lock.lock().unwrap();
+
+        if let Some(path) = &self.path {
+            let content = serde_json::to_string(schedules)
+                .map_err(|err| SchedulingError::SerializationError(err.to_string()))?;
+
+            fs::write(path, content)?;
+        }
+
+        Result::Ok(())
+    }
+
+}
+
+impl From<std::io::Error> for SchedulingError {
+    fn from(err: std::io::Error) -> Self {
+        SchedulingError::IOError(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Europe::Warsaw;
+
+    use super::*;
+
+    #[test]
+    fn test_finds_next_cron_start_in_time_zone() {
+        let definition = ScheduleDefinition::Cron(
+            "30 6 * * *".to_owned(), TzWrapper::new(Warsaw));
+
+        assert_eq!(
+            Ok(Some(NaiveDateTime::from_str("2021-01-02T05:30:00").unwrap())),
+            definition.get_next_start(&NaiveDateTime::from_str("2021-01-01T05:30:00").unwrap()));
+        assert_eq!(
+            Ok(Some(NaiveDateTime::from_str("2021-07-01T04:30:00").unwrap())),
+            definition.get_next_start(&NaiveDateTime::from_str("2021-06-30T05:00:00").unwrap()));
+        assert!(ScheduleDefinition::Cron("30 6 *".to_owned(), TzWrapper::new(Warsaw))
+            .validate(&NaiveDateTime::from_str("2021-01-01T05:30:00").unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_starts_once_at_given_time() {
+        let definition = ScheduleDefinition::At(
+            ZonedDateTime::new(NaiveDateTime::from_str("2021-01-01T12:00:00").unwrap(), Warsaw));
+
+        assert_eq!(
+            Ok(Some(NaiveDateTime::from_str("2021-01-01T11:00:00").unwrap())),
+            definition.get_next_start(&NaiveDateTime::from_str("2021-01-01T10:00:00").unwrap()));
+        assert_eq!(
+            Ok(None),
+            definition.get_next_start(&NaiveDateTime::from_str("2021-01-01T11:00:00").unwrap()));
+        assert_eq!(
+            Err(SchedulingError::StartInPast),
+            definition.validate(&NaiveDateTime::from_str("2021-01-01T11:00:00").unwrap()));
+    }
+
+}
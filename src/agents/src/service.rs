@@ -1,26 +1,43 @@
+use std::collections::{HashMap, HashSet};
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex};
 
 use actix::Arbiter;
 use dashmap::DashMap;
-use futures::future::AbortHandle;
+use futures::future::{AbortHandle, Abortable};
 use futures::io::Error;
+use log::warn;
 use serde::{Deserialize, Serialize};
-use tokio::runtime::Runtime;
+use serde_json::{Map, Value};
+use tokio::runtime::{Handle, Runtime};
 use uuid::Uuid;
 
+use buttercup_blackboards::LocalBlackboardError;
 use buttercup_bts::context::{BTNodeContextService, BTNodeContextServiceError};
-use buttercup_bts::tree::BehaviorTreeService;
-use buttercup_endpoints::endpoints::EndpointService;
+use buttercup_bts::context::debug::{DebugContext, DebugContextError, DebugState};
+use buttercup_bts::context::recording::{ExecutionRecording, RecordingContext, RecordingContextError};
+use buttercup_bts::tree::{BehaviorTree, BehaviorTreeService};
+use buttercup_endpoints::endpoints::EndpointError;
+use buttercup_endpoints::extraction::{ArgumentValuesExtractionService, TypedValue};
+use buttercup_values::{ValueHolder, ValuesPayload};
 
-use crate::Agent;
+use crate::{Agent, AgentDefinition, AgentError, AgentStatus};
+use crate::events::AgentEventStream;
+use crate::listing::{AgentFilter, AgentsPage, Pagination};
+use crate::scheduling::{PersistedSchedule, Schedule, ScheduleDefinition, ScheduleStore, SchedulingError};
 use crate::service::AgentServiceError::AgentAlreadyStarted;
 
+///
+/// Spawned schedules by their ids, along with the agents they start.
+///
+type Schedules = DashMap<Uuid, (Schedule, Arc<Agent>, AbortHandle)>;
+
 pub struct AgentService {
 
+    agents: DashMap<Uuid, Arc<Agent>>,
     context_service: Arc<BTNodeContextService>,
-    started_agents: DashMap<Uuid, (Arc<Agent>, AbortHandle)>,
-    stopped_agents: DashMap<Uuid, Arc<Agent>>,
+    schedules: Arc<Schedules>,
+    schedule_store: Arc<ScheduleStore>,
     tree_service: Arc<BehaviorTreeService>,
     runtime: Runtime
 
@@ -29,12 +46,14 @@ pub struct AgentService {
 impl AgentService {
 
     pub fn new(context_service: Arc<BTNodeContextService>,
+               schedule_store: ScheduleStore,
                tree_service: Arc<BehaviorTreeService>) -> Result<AgentService, AgentServiceError> {
         Result::Ok(
             AgentService {
-                stopped_agents: DashMap::new(),
-                started_agents: DashMap::new(),
+                agents: DashMap::new(),
                 context_service,
+                schedules: Arc::new(DashMap::new()),
+                schedule_store: Arc::new(schedule_store),
                 tree_service,
                 runtime: Runtime::new()?
             }
@@ -42,75 +61,542 @@ impl AgentService {
     }
 
     pub fn build_new_agent(&self,
-                           tree_id: &i32) -> Result<Uuid, AgentServiceError> {
-        if let Some(tree) = self.tree_service.get_by_id(tree_id) {
-            let context =
-                Arc::new(self.context_service.build_new()?);
+                           definition: AgentDefinition) -> Result<Uuid, AgentServiceError> {
+        self.build_agent(Uuid::new_v4(), definition, None)
+    }
 
-            let agent_id = Uuid::new_v4();
-            self.stopped_agents.insert(agent_id,
-                                       Arc::new(
-                                           Agent::new(agent_id.clone(), context, tree)));
+    ///
+    /// Runs the agent in the background, restarting it as its restart policy allows.
+    /// Its results are kept and its state updated once it ends.
+    ///
+    pub fn start_agent_by_id(&self,
+                             agent_id: &Uuid) -> Result<(), AgentServiceError> {
+        spawn_run(self.runtime.handle(), self.get_agent(agent_id)?)
+    }
 
-            return Result::Ok(agent_id);
+    pub fn stop_agent_by_id(&self,
+                            agent_id: &Uuid) -> Result<(), AgentServiceError> {
+        if self.get_agent(agent_id)?.stop() {
+            return Result::Ok(());
         }
 
-        Result::Err(AgentServiceError::TreeOfGivenIdNotFound(*tree_id))
+        Result::Err(AgentServiceError::AgentNotRunning)
     }
 
-    pub fn start_agent_by_id(&self,
-                             agent_id: &Uuid) -> Result<(), AgentServiceError> {
-        match self.stopped_agents.remove(agent_id) {
-            None => {
-                if self.started_agents.contains_key(agent_id) {
-                    return Result::Err(AgentServiceError::AgentAlreadyStarted);
-                }
-                return Result::Err(AgentServiceError::AgentOfGivenIdNotFound);
-            }
-            Some(agent_entry) => {
-                let agent = agent_entry.1;
-                let (abort_handle, abort_registration) =
-                    AbortHandle::new_pair();
+    pub fn list_agents(&self,
+                       filter: &AgentFilter,
+                       pagination: &Pagination) -> AgentsPage {
+        AgentsPage::new(
+            self.agents
+                .iter()
+                .map(|entry| entry.value().get_summary())
+                .filter(|summary| filter.matches(summary))
+                .collect(),
+            pagination)
+    }
+
+    pub fn get_agent_status(&self,
+                            agent_id: &Uuid) -> Result<AgentStatus, AgentServiceError> {
+        Result::Ok(self.get_agent(agent_id)?.get_status())
+    }
 
-                let agent_ref = agent.clone();
+    ///
+    /// Removes the schedules of the agent, aborts the agent and waits for its run to end,
+    /// then releases its listener, destroys its blackboard and unpins its tree. The agent
+    /// is kept until all of that succeeds.
+    ///
+    pub async fn delete_agent_by_id(&self,
+                                    agent_id: &Uuid) -> Result<(), AgentServiceError> {
+        let agent = self.get_agent(agent_id)?;
 
-                self.runtime.spawn(async move {
-                    agent.start(abort_registration).await
-                });
+        self.persist_schedules_where(|schedule| schedule.get_agent_id() != agent_id)?;
 
-                self.started_agents.insert(agent_id.clone(), (agent_ref, abort_handle));
+        self.schedules.retain(|_, (schedule, _, abort_handle)| {
+            if schedule.get_agent_id() != agent_id {
+                return true;
             }
+
+            abort_handle.abort();
+
+            false
+        });
+
+        let run_task = agent.close();
+        let context = agent.get_context();
+
+        context.get_context().get_debug_context().disable();
+
+        if let Some(run_task) = run_task {
+            let _ = run_task.await;
         }
 
+        self.context_service.destroy(context)?;
+        self.agents.remove(agent_id);
+
+        let tree = agent.get_tree();
+        self.tree_service.unpin(tree.get_id(), tree.get_version());
+
         Result::Ok(())
     }
 
-    pub fn stop_agent_by_id(&self,
-                            agent_id: &Uuid) -> Result<(), AgentServiceError> {
-        match self.started_agents.remove(agent_id) {
-            None => {
-                return Result::Err(AgentServiceError::AgentOfGivenIdNotFound);
-            }
-            Some(agent_entry) => {
-                let (agent, abort_handle) = agent_entry.1;
+    ///
+    /// Starts the agent on each occurrence of the schedule, skipping the ones
+    /// at which it is still running. Schedules are persisted in the store, until
+    /// they cannot start the agent anymore, e.g. once a one-shot schedule fired.
+    ///
+    pub fn schedule_agent(&self,
+                          agent_id: &Uuid,
+                          definition: ScheduleDefinition) -> Result<Uuid, AgentServiceError> {
+        let agent = self.get_agent(agent_id)?;
+
+        definition.validate(&agent.now())?;
+
+        let schedule = Schedule::new(Uuid::new_v4(), *agent_id, definition);
+        let schedule_id = *schedule.get_id();
+
+        self.spawn_schedule(schedule, agent);
+        self.persist_schedules()?;
+
+        Result::Ok(schedule_id)
+    }
+
+    pub fn get_schedules(&self,
+                         agent_id: &Uuid) -> Result<Vec<Schedule>, AgentServiceError> {
+        self.get_agent(agent_id)?;
+
+        Result::Ok(
+            self.schedules
+                .iter()
+                .map(|entry| entry.value().0.clone())
+                .filter(|schedule| schedule.get_agent_id() == agent_id)
+                .collect())
+    }
+
+    pub fn delete_schedule(&self,
+                           agent_id: &Uuid,
+                           schedule_id: &Uuid) -> Result<(), AgentServiceError> {
+        match self.schedules.remove_if(
+            schedule_id, |_, (schedule, _, _)| schedule.get_agent_id() == agent_id) {
+            None => Result::Err(AgentServiceError::ScheduleOfGivenIdNotFound),
+            Some((_, (_, _, abort_handle))) => {
                 abort_handle.abort();
 
-                self.stopped_agents.insert(agent_entry.0, agent);
+                self.persist_schedules()
+            }
+        }
+    }
+
+    ///
+    /// Brings back the persisted schedules, rebuilding their agents under the same ids
+    /// over their blackboards. Each schedule is restored on its own, the ones which cannot be
+    /// are logged and skipped. Returns the number of the restored schedules.
+    ///
+    pub fn restore_schedules(&self) -> Result<usize, AgentServiceError> {
+        let mut restored = 0;
+
+        for persisted_schedule in self.schedule_store.load()? {
+            match self.restore_schedule(&persisted_schedule) {
+                Ok(_) => restored += 1,
+                Err(err) => warn!("Schedule {} could not be restored: {:?}",
+                                  persisted_schedule.get_schedule().get_id(), err)
             }
         }
 
+        Result::Ok(restored)
+    }
+
+    ///
+    /// Extracts the values of the endpoint declared by the tree of the agent and writes them
+    /// into the blackboard of the agent, notifying its listeners.
+    ///
+    pub fn accept_endpoint_values(&self,
+                                  agent_id: &Uuid,
+                                  endpoint_name: &str,
+                                  payload: &Value) -> Result<(), AgentServiceError> {
+        let agent = self.get_agent(agent_id)?;
+
+        let values = agent
+            .get_tree()
+            .get_endpoints()
+            .get(endpoint_name)
+            .ok_or_else(|| AgentServiceError::EndpointOfGivenNameNotFound(endpoint_name.to_owned()))?
+            .extract(payload)
+            .map_err(|err| AgentServiceError::InvalidArguments(format!("{:?}", err)))?;
+
+        Result::Ok(
+            self.context_service
+                .get_endpoint_service()
+                .accept_value_changes(agent.get_context().get_id(), values)?)
+    }
+
+    ///
+    /// Puts the typed values into the blackboard of the agent, keeping the other ones.
+    ///
+    pub fn patch_values(&self,
+                        agent_id: &Uuid,
+                        values: &HashMap<String, TypedValue>) -> Result<(), AgentServiceError> {
+        let blackboard_id = self.get_blackboard_id(agent_id)?;
+
+        Result::Ok(
+            self.context_service
+                .get_endpoint_service()
+                .accept_value_changes(&blackboard_id, extract_typed_values(values)?)?)
+    }
+
+    ///
+    /// Replaces all the values in the blackboard of the agent with the typed values.
+    ///
+    pub fn replace_values(&self,
+                          agent_id: &Uuid,
+                          values: &HashMap<String, TypedValue>) -> Result<(), AgentServiceError> {
+        let blackboard_id = self.get_blackboard_id(agent_id)?;
+
+        Result::Ok(
+            self.context_service
+                .get_endpoint_service()
+                .replace_values(&blackboard_id, extract_typed_values(values)?)?)
+    }
+
+    pub fn get_values(&self,
+                      agent_id: &Uuid) -> Result<ValuesPayload, AgentServiceError> {
+        let blackboard_id = self.get_blackboard_id(agent_id)?;
+
+        Result::Ok(self.context_service.get_endpoint_service().get_values(&blackboard_id)?)
+    }
+
+    pub fn get_value(&self,
+                     agent_id: &Uuid,
+                     value_name: &String) -> Result<Option<ValueHolder>, AgentServiceError> {
+        let blackboard_id = self.get_blackboard_id(agent_id)?;
+
+        Result::Ok(
+            self.context_service
+                .get_endpoint_service()
+                .get_value(&blackboard_id, value_name)?)
+    }
+
+    pub fn subscribe_to_events(&self,
+                               agent_id: &Uuid) -> Result<AgentEventStream, AgentServiceError> {
+        let agent = self.get_agent(agent_id)?;
+
+        Result::Ok(
+            AgentEventStream::subscribe(
+                agent.get_context().get_context().get_event_sink().clone()))
+    }
+
+    pub fn enable_debugging(&self,
+                            agent_id: &Uuid,
+                            breakpoints: HashSet<i32>) -> Result<(), AgentServiceError> {
+        self.with_debug_context(agent_id,
+                                |debug_context| debug_context.enable(breakpoints))
+    }
+
+    pub fn disable_debugging(&self,
+                             agent_id: &Uuid) -> Result<(), AgentServiceError> {
+        self.with_debug_context(agent_id, DebugContext::disable)
+    }
+
+    pub fn add_breakpoint(&self,
+                          agent_id: &Uuid,
+                          node_id: i32) -> Result<(), AgentServiceError> {
+        Result::Ok(
+            self.with_debug_context(agent_id,
+                                    |debug_context| debug_context.add_breakpoint(node_id))??)
+    }
+
+    pub fn remove_breakpoint(&self,
+                             agent_id: &Uuid,
+                             node_id: &i32) -> Result<(), AgentServiceError> {
+        Result::Ok(
+            self.with_debug_context(agent_id,
+                                    |debug_context| debug_context.remove_breakpoint(node_id))??)
+    }
+
+    pub fn step_agent_by_id(&self,
+                            agent_id: &Uuid) -> Result<(), AgentServiceError> {
+        Result::Ok(self.with_debug_context(agent_id, DebugContext::step)??)
+    }
+
+    pub fn resume_agent_by_id(&self,
+                              agent_id: &Uuid) -> Result<(), AgentServiceError> {
+        Result::Ok(self.with_debug_context(agent_id, DebugContext::resume)??)
+    }
+
+    pub fn get_debug_state(&self,
+                           agent_id: &Uuid) -> Result<DebugState, AgentServiceError> {
+        self.with_debug_context(agent_id, DebugContext::get_state)
+    }
+
+    pub fn start_recording(&self,
+                           agent_id: &Uuid) -> Result<(), AgentServiceError> {
+        Result::Ok(self.with_recording_context(agent_id, RecordingContext::start_recording)??)
+    }
+
+    pub fn stop_recording(&self,
+                          agent_id: &Uuid) -> Result<ExecutionRecording, AgentServiceError> {
+        Result::Ok(self.with_recording_context(agent_id, RecordingContext::stop_recording)??)
+    }
+
+    pub fn get_recording(&self,
+                         agent_id: &Uuid) -> Result<ExecutionRecording, AgentServiceError> {
+        Result::Ok(self.with_recording_context(agent_id, RecordingContext::get_recording)??)
+    }
+
+    ///
+    /// Moves the agent, unless it is running, to the given version of its tree, the latest one
+    /// if none is given, keeping its blackboard. Arguments of the agent have to match the new
+    /// version, the ones missing from the blackboard are put to it. The new version is pinned
+    /// in place of the previous one. Returns the version the agent was moved to.
+    ///
+    pub fn migrate_agent(&self,
+                         agent_id: &Uuid,
+                         tree_version: &Option<u32>) -> Result<u32, AgentServiceError> {
+        let agent = self.get_agent(agent_id)?;
+        let definition = agent.get_definition();
+        let tree = self.get_tree(definition.get_tree_id(), tree_version)?;
+        let version = *tree.get_version();
+
+        let blackboard_id = *agent.get_context().get_id();
+        let endpoint_service = self.context_service.get_endpoint_service();
+        let present_values = endpoint_service.get_values(&blackboard_id)?;
+        let missing_values: HashMap<String, ValueHolder> = extract_arguments(&tree, &definition)?
+            .into_values()
+            .into_iter()
+            .filter(|(name, _)| !present_values.get_keys().contains(name))
+            .collect();
+
+        let previous_tree = agent.get_tree();
+
+        agent
+            .migrate(tree)
+            .map_err(|_| AgentServiceError::AgentAlreadyStarted)?;
+
+        self.tree_service.pin(definition.get_tree_id(), &version);
+        self.tree_service.unpin(previous_tree.get_id(), previous_tree.get_version());
+
+        if !missing_values.is_empty() {
+            endpoint_service
+                .accept_value_changes(&blackboard_id, ValuesPayload::new(missing_values))?;
+        }
+
+        self.persist_schedules()?;
+
+        Result::Ok(version)
+    }
+
+    ///
+    /// Builds a new agent of the given tree, which replays the recording once started.
+    ///
+    pub fn build_replaying_agent(&self,
+                                 tree_id: &i32,
+                                 recording: &ExecutionRecording) -> Result<Uuid, AgentServiceError> {
+        let agent_id = self.build_new_agent((*tree_id).into())?;
+
+        self.with_recording_context(
+            &agent_id, |context| context.start_replay(recording))??;
+
+        Result::Ok(agent_id)
+    }
+
+    ///
+    /// Blackboard of the agent is registered under the id of its context.
+    ///
+    pub fn get_blackboard_id(&self,
+                             agent_id: &Uuid) -> Result<Uuid, AgentServiceError> {
+        Result::Ok(*self.get_agent(agent_id)?.get_context().get_id())
+    }
+
+    ///
+    /// Builds the agent over a new blackboard, or reopens the given one. The version of the tree
+    /// is pinned for as long as the agent executes it.
+    ///
+    fn build_agent(&self,
+                   agent_id: Uuid,
+                   definition: AgentDefinition,
+                   blackboard_id: Option<&Uuid>) -> Result<Uuid, AgentServiceError> {
+        let tree = self.get_tree(definition.get_tree_id(), definition.get_tree_version())?;
+
+        let initial_values = extract_arguments(&tree, &definition)?;
+
+        let context = match blackboard_id {
+            None => self.context_service.build_with_values(&initial_values)?,
+            Some(blackboard_id) =>
+                self.context_service.reopen_with_values(blackboard_id, &initial_values)?
+        };
+
+        self.tree_service.pin(tree.get_id(), tree.get_version());
+        self.agents.insert(agent_id,
+                           Arc::new(Agent::new(agent_id, Arc::new(context), definition, tree)));
+
+        Result::Ok(agent_id)
+    }
+
+    fn restore_schedule(&self,
+                        persisted_schedule: &PersistedSchedule) -> Result<(), AgentServiceError> {
+        let schedule = persisted_schedule.get_schedule();
+        let agent_id = schedule.get_agent_id();
+
+        if !self.agents.contains_key(agent_id) {
+            self.build_agent(*agent_id,
+                             persisted_schedule.get_agent().clone(),
+                             Some(persisted_schedule.get_blackboard_id()))?;
+        }
+
+        self.spawn_schedule(schedule.clone(), self.get_agent(agent_id)?);
+
         Result::Ok(())
     }
+
+    ///
+    /// The schedule removes itself once it cannot start the agent anymore.
+    ///
+    fn spawn_schedule(&self,
+                      schedule: Schedule,
+                      agent: Arc<Agent>) {
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let schedule_id = *schedule.get_id();
+        let definition = schedule.get_definition().clone();
+        let handle = self.runtime.handle().clone();
+        let schedules = self.schedules.clone();
+        let schedule_store = self.schedule_store.clone();
+
+        self.schedules.insert(schedule_id, (schedule, agent.clone(), abort_handle));
+
+        self.runtime.spawn(Abortable::new(
+            async move {
+                while let Ok(Some(next_start)) = definition.get_next_start(&agent.now()) {
+                    let delay = (next_start - agent.now()).to_std().unwrap_or_default();
+
+                    agent.get_context().get_context().get_clock().sleep(delay).await;
+
+                    let _ = spawn_run(&handle, agent.clone());
+                }
+
+                schedules.remove(&schedule_id);
+
+                if let Err(err) = persist_schedules_where(&schedules, &schedule_store, |_| true) {
+                    warn!("Schedules could not be persisted: {:?}", err);
+                }
+            },
+            abort_registration));
+    }
+
+    fn persist_schedules(&self) -> Result<(), AgentServiceError> {
+        self.persist_schedules_where(|_| true)
+    }
+
+    fn persist_schedules_where<F>(&self,
+                                  predicate: F) -> Result<(), AgentServiceError>
+        where F: Fn(&Schedule) -> bool {
+        persist_schedules_where(&self.schedules, &self.schedule_store, predicate)
+    }
+
+    fn with_debug_context<T, F>(&self,
+                                agent_id: &Uuid,
+                                action: F) -> Result<T, AgentServiceError>
+        where F: FnOnce(&DebugContext) -> T {
+        Result::Ok(
+            action(self.get_agent(agent_id)?
+                .get_context()
+                .get_context()
+                .get_debug_context()))
+    }
+
+    fn with_recording_context<T, F>(&self,
+                                    agent_id: &Uuid,
+                                    action: F) -> Result<T, AgentServiceError>
+        where F: FnOnce(&RecordingContext) -> T {
+        Result::Ok(
+            action(self.get_agent(agent_id)?
+                .get_context()
+                .get_context()
+                .get_recording_context()))
+    }
+
+    fn get_tree(&self,
+                tree_id: &i32,
+                tree_version: &Option<u32>) -> Result<Arc<BehaviorTree>, AgentServiceError> {
+        match tree_version {
+            None => self.tree_service
+                .get_by_id(tree_id)
+                .ok_or(AgentServiceError::TreeOfGivenIdNotFound(*tree_id)),
+            Some(version) => self.tree_service
+                .get_version(tree_id, version)
+                .ok_or(AgentServiceError::TreeVersionNotFound(*tree_id, *version))
+        }
+    }
+
+    fn get_agent(&self,
+                 agent_id: &Uuid) -> Result<Arc<Agent>, AgentServiceError> {
+        self.agents
+            .get(agent_id)
+            .map(|entry| entry.value().clone())
+            .ok_or(AgentServiceError::AgentOfGivenIdNotFound)
+    }
+}
+
+fn extract_typed_values(values: &HashMap<String, TypedValue>) -> Result<ValuesPayload, AgentServiceError> {
+    ArgumentValuesExtractionService::process_typed(values)
+        .map_err(|err| AgentServiceError::InvalidArguments(format!("{:?}", err)))
+}
+
+fn extract_arguments(tree: &BehaviorTree,
+                     definition: &AgentDefinition) -> Result<ValuesPayload, AgentServiceError> {
+    tree
+        .get_arguments()
+        .extract(definition.get_arguments().as_ref().unwrap_or(&Value::Object(Map::new())))
+        .map_err(|err| AgentServiceError::InvalidArguments(format!("{:?}", err)))
+}
+
+fn persist_schedules_where<F>(schedules: &Schedules,
+                              schedule_store: &ScheduleStore,
+                              predicate: F) -> Result<(), AgentServiceError>
+    where F: Fn(&Schedule) -> bool {
+    let persisted_schedules: Vec<PersistedSchedule> = schedules
+        .iter()
+        .filter(|entry| predicate(&entry.value().0))
+        .map(|entry| {
+            let (schedule, agent, _) = entry.value();
+
+            PersistedSchedule::new(
+                agent.get_definition(), *agent.get_context().get_id(), schedule.clone())
+        })
+        .collect();
+
+    Result::Ok(schedule_store.save(&persisted_schedules)?)
+}
+
+///
+/// Runs the agent in the background, restarting it as its restart policy allows.
+///
+fn spawn_run(handle: &Handle,
+             agent: Arc<Agent>) -> Result<(), AgentServiceError> {
+    agent
+        .spawn_run(handle)
+        .map_err(|err| match err {
+            AgentError::Closed => AgentServiceError::AgentOfGivenIdNotFound,
+            _ => AgentServiceError::AgentAlreadyStarted
+        })
 }
 
 #[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
 pub enum AgentServiceError {
 
     AgentAlreadyStarted,
+    AgentNotRunning,
     AgentOfGivenIdNotFound,
+    BlackboardError(LocalBlackboardError),
     BTNodeContextServiceError(BTNodeContextServiceError),
+    DebugContextError(DebugContextError),
+    EndpointError(EndpointError),
+    EndpointOfGivenNameNotFound(String),
+    InvalidArguments(String),
     IOError(String),
-    TreeOfGivenIdNotFound(i32)
+    RecordingContextError(RecordingContextError),
+    ScheduleOfGivenIdNotFound,
+    SchedulingError(SchedulingError),
+    TreeOfGivenIdNotFound(i32),
+    TreeVersionNotFound(i32, u32)
 
 }
 
@@ -120,8 +606,533 @@ impl From<BTNodeContextServiceError> for AgentServiceError {
     }
 }
 
+impl From<DebugContextError> for AgentServiceError {
+    fn from(err: DebugContextError) -> Self {
+        AgentServiceError::DebugContextError(err)
+    }
+}
+
+impl From<RecordingContextError> for AgentServiceError {
+    fn from(err: RecordingContextError) -> Self {
+        AgentServiceError::RecordingContextError(err)
+    }
+}
+
+impl From<EndpointError> for AgentServiceError {
+    fn from(err: EndpointError) -> Self {
+        AgentServiceError::EndpointError(err)
+    }
+}
+
+impl From<SchedulingError> for AgentServiceError {
+    fn from(err: SchedulingError) -> Self {
+        AgentServiceError::SchedulingError(err)
+    }
+}
+
 impl From<std::io::Error> for AgentServiceError {
     fn from(err: Error) -> Self {
         AgentServiceError::IOError(err.to_string())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use actix_rt::{System, SystemRunner};
+
+    use chrono_tz::Tz;
+    use num::BigInt;
+    use serde_json::json;
+
+    use buttercup_blackboards::LocalBlackboardService;
+    use buttercup_bts::context::test_utils;
+    use buttercup_bts::node::action::logging::PrintLogActionNode;
+    use buttercup_bts::node::root::one_off::OneOffRootBTNode;
+    use buttercup_bts::tree::BehaviorTreeServiceError;
+    use buttercup_endpoints::endpoints::EndpointService;
+    use buttercup_endpoints::{ArgumentDefinition, ArgumentsExtractor, ArgumentSetDefinition};
+    use buttercup_values::ValueType;
+    use buttercup_values::email::Email;
+    use buttercup_values::extractors::ValueExtractionPolicy;
+    use buttercup_values::wrappers::{TzWrapper, Wrapper};
+    use buttercup_values::zoned_date_time::ZonedDateTime;
+
+    use crate::{AgentState, ReactiveErrorPolicy};
+    use crate::supervision::RestartPolicy;
+
+    use super::*;
+
+    ///
+    /// Agent service over the given trees, destroying the blackboards of the agents
+    /// left in the service once dropped.
+    ///
+    struct TestServices {
+
+        agent_service: AgentService,
+        blackboard_service: Arc<LocalBlackboardService>,
+        endpoint_service: Arc<EndpointService>,
+        system: SystemRunner
+
+    }
+
+    impl TestServices {
+
+        fn new(trees: Vec<BehaviorTree>) -> TestServices {
+            TestServices::with_schedule_store(ScheduleStore::default(), trees)
+        }
+
+        fn with_schedule_store(schedule_store: ScheduleStore,
+                               trees: Vec<BehaviorTree>) -> TestServices {
+            let system = System::new();
+            let blackboard_service = Arc::new(LocalBlackboardService::default());
+            let endpoint_service =
+                Arc::new(EndpointService::new(Arbiter::new(), blackboard_service.clone()));
+            let tree_service = Arc::new(BehaviorTreeService::default());
+
+            for tree in trees {
+                tree_service.insert(tree);
+            }
+
+            let agent_service = AgentService::new(
+                Arc::new(BTNodeContextService::new(endpoint_service.clone(),
+                                                   blackboard_service.clone())),
+                schedule_store,
+                tree_service).unwrap();
+
+            TestServices {
+                agent_service,
+                blackboard_service,
+                endpoint_service,
+                system
+            }
+        }
+
+    }
+
+    impl TestServices {
+
+        ///
+        /// Closes the blackboards of the agents without destroying them, as exiting would.
+        ///
+        fn shut_down(self) {
+            for entry in self.agent_service.agents.iter() {
+                if let Ok(blackboard) =
+                self.blackboard_service.get(entry.value().get_context().get_id()) {
+                    blackboard.close().unwrap();
+                }
+            }
+
+            self.agent_service.agents.clear();
+        }
+
+    }
+
+    impl Drop for TestServices {
+        fn drop(&mut self) {
+            for entry in self.agent_service.agents.iter() {
+                let _ = self.agent_service.context_service.destroy(entry.value().get_context());
+            }
+        }
+    }
+
+    #[test]
+    fn test_deletes_agent_with_its_blackboard_and_listener() {
+        let services = TestServices::new(vec![print_log_tree(1, "Deleted.")]);
+        let agent_service = &services.agent_service;
+        let blackboard_service = &services.blackboard_service;
+        let endpoint_service = &services.endpoint_service;
+
+        let agent_id = agent_service.build_new_agent(1.into()).unwrap();
+        let path = test_utils::get_path(
+            agent_service.get_agent(&agent_id).unwrap().get_context().get_context());
+
+        assert!(!blackboard_service.is_empty());
+        assert_eq!(1, endpoint_service.get_listeners_count());
+
+        agent_service.start_agent_by_id(&agent_id).unwrap();
+        services.system.block_on(agent_service.delete_agent_by_id(&agent_id)).unwrap();
+
+        assert!(!std::path::Path::new(&path).exists());
+        assert!(blackboard_service.is_empty());
+        assert_eq!(0, endpoint_service.get_listeners_count());
+        assert_eq!(Ok(true), agent_service.tree_service.remove(&1));
+        assert_eq!(Result::Err(AgentServiceError::AgentOfGivenIdNotFound),
+                   agent_service.get_blackboard_id(&agent_id));
+    }
+
+    #[test]
+    fn test_builds_agent_with_initial_values_of_tree_arguments() {
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "recipient".to_owned(),
+            ArgumentDefinition::new(
+                1, "recipient".to_owned(), ValueType::Email, ValueExtractionPolicy::Strict, 1));
+
+        let services = TestServices::new(vec![
+            BehaviorTree::with_arguments(
+                1,
+                ArgumentsExtractor::new(arguments),
+                Vec::new(),
+                OneOffRootBTNode::new(
+                    2, PrintLogActionNode::new(3, "Parametrized.".to_owned()).into()).into())]);
+        let agent_service = &services.agent_service;
+
+        let build_agent = |arguments|
+            agent_service.build_new_agent(
+                AgentDefinition::new(
+                    arguments,
+                    HashMap::new(),
+                    ReactiveErrorPolicy::default(),
+                    RestartPolicy::default(),
+                    1,
+                    None));
+
+        assert!(matches!(build_agent(None), Err(AgentServiceError::InvalidArguments(_))));
+        assert!(matches!(build_agent(Some(json!({"recipient": "not an email"}))),
+                         Err(AgentServiceError::InvalidArguments(_))));
+        assert!(services.blackboard_service.is_empty());
+
+        let agent_id = build_agent(Some(json!({"recipient": "ops@example.com"}))).unwrap();
+
+        assert_eq!(
+            Some(ValueHolder::Email(Email::new("ops@example.com").unwrap())),
+            agent_service.get_agent(&agent_id)
+                .unwrap()
+                .get_context()
+                .get_context()
+                .get_value(&"recipient".to_owned())
+                .unwrap());
+    }
+
+    #[test]
+    fn test_accepts_values_of_endpoints_declared_by_tree() {
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "threshold".to_owned(),
+            ArgumentDefinition::new(
+                1, "threshold".to_owned(), ValueType::Integer, ValueExtractionPolicy::Lax, 1));
+
+        let services = TestServices::new(vec![
+            BehaviorTree::with_arguments(
+                1,
+                ArgumentsExtractor::default(),
+                vec![
+                    ArgumentSetDefinition::new(
+                        1,
+                        "alerts".to_owned(),
+                        "Alerting thresholds.".to_owned(),
+                        ArgumentsExtractor::new(arguments))
+                ],
+                OneOffRootBTNode::new(
+                    2, PrintLogActionNode::new(3, "Alerting.".to_owned()).into()).into())]);
+        let agent_service = &services.agent_service;
+
+        let agent_id = agent_service.build_new_agent(1.into()).unwrap();
+
+        assert_eq!(
+            Err(AgentServiceError::EndpointOfGivenNameNotFound("metrics".to_owned())),
+            agent_service.accept_endpoint_values(&agent_id, "metrics", &json!({"threshold": 5})));
+        assert!(matches!(
+            agent_service.accept_endpoint_values(&agent_id, "alerts", &json!({"threshold": "high"})),
+            Err(AgentServiceError::InvalidArguments(_))));
+
+        agent_service.accept_endpoint_values(&agent_id, "alerts", &json!({"threshold": "5"})).unwrap();
+
+        assert_eq!(
+            Some(ValueHolder::Integer(BigInt::from(5))),
+            agent_service.get_agent(&agent_id)
+                .unwrap()
+                .get_context()
+                .get_context()
+                .get_value(&"threshold".to_owned())
+                .unwrap());
+    }
+
+    #[test]
+    fn test_patches_and_replaces_typed_values_of_agent() {
+        let services = TestServices::new(vec![print_log_tree(1, "Fed.")]);
+        let agent_service = &services.agent_service;
+
+        let agent_id = agent_service.build_new_agent(1.into()).unwrap();
+        let other_agent_id = agent_service.build_new_agent(1.into()).unwrap();
+
+        let typed_values = |name: &str, value, value_type| {
+            let mut values = HashMap::new();
+            values.insert(
+                name.to_owned(),
+                TypedValue::new(value, value_type, ValueExtractionPolicy::Strict));
+            values
+        };
+
+        agent_service.patch_values(
+            &agent_id, &typed_values("retries", json!(3), ValueType::Integer)).unwrap();
+        agent_service.patch_values(
+            &agent_id, &typed_values("enabled", json!(true), ValueType::Boolean)).unwrap();
+
+        assert!(matches!(
+            agent_service.patch_values(
+                &agent_id, &typed_values("retries", json!("three"), ValueType::Integer)),
+            Err(AgentServiceError::InvalidArguments(_))));
+        assert_eq!(2, agent_service.get_values(&agent_id).unwrap().get_values().len());
+        assert_eq!(Ok(Some(ValueHolder::Integer(BigInt::from(3)))),
+                   agent_service.get_value(&agent_id, &"retries".to_owned()));
+        assert!(agent_service.get_values(&other_agent_id).unwrap().get_values().is_empty());
+
+        agent_service.replace_values(
+            &agent_id, &typed_values("enabled", json!(false), ValueType::Boolean)).unwrap();
+
+        assert_eq!(Ok(None), agent_service.get_value(&agent_id, &"retries".to_owned()));
+        assert_eq!(Ok(Some(ValueHolder::Boolean(false))),
+                   agent_service.get_value(&agent_id, &"enabled".to_owned()));
+    }
+
+    #[test]
+    fn test_migrates_agent_between_tree_versions_keeping_values() {
+        let services = TestServices::new(
+            vec![print_log_tree(1, "First."), print_log_tree(1, "Second.")]);
+        let agent_service = &services.agent_service;
+
+        let agent_id = agent_service.build_new_agent(
+            AgentDefinition::new(
+                None,
+                HashMap::new(),
+                ReactiveErrorPolicy::default(),
+                RestartPolicy::default(),
+                1,
+                Some(1))).unwrap();
+
+        assert_eq!(1, *agent_service.get_agent_status(&agent_id).unwrap().get_tree_version());
+        assert_eq!(
+            Err(AgentServiceError::TreeVersionNotFound(1, 3)),
+            agent_service.build_new_agent(
+                AgentDefinition::new(
+                    None,
+                    HashMap::new(),
+                    ReactiveErrorPolicy::default(),
+                    RestartPolicy::default(),
+                    1,
+                    Some(3))));
+
+        let mut values = HashMap::new();
+        values.insert(
+            "retries".to_owned(),
+            TypedValue::new(json!(3), ValueType::Integer, ValueExtractionPolicy::Strict));
+
+        agent_service.patch_values(&agent_id, &values).unwrap();
+
+        assert_eq!(Err(AgentServiceError::TreeVersionNotFound(1, 3)),
+                   agent_service.migrate_agent(&agent_id, &Some(3)));
+        assert_eq!(Ok(2), agent_service.migrate_agent(&agent_id, &None));
+        assert_eq!(2, *agent_service.get_agent_status(&agent_id).unwrap().get_tree_version());
+        assert_eq!(Some(2),
+                   *agent_service.get_agent(&agent_id).unwrap().get_definition().get_tree_version());
+        assert_eq!(Ok(Some(ValueHolder::Integer(BigInt::from(3)))),
+                   agent_service.get_value(&agent_id, &"retries".to_owned()));
+        assert_eq!(Err(BehaviorTreeServiceError::TreeVersionIsPinned(1, 2)),
+                   agent_service.tree_service.remove(&1));
+    }
+
+    #[test]
+    fn test_migrates_agent_only_to_tree_matching_its_arguments() {
+        let mut arguments = HashMap::new();
+        arguments.insert(
+            "recipient".to_owned(),
+            ArgumentDefinition::new(
+                1, "recipient".to_owned(), ValueType::Email, ValueExtractionPolicy::Strict, 1));
+
+        let services = TestServices::new(vec![
+            print_log_tree(1, "Plain."),
+            BehaviorTree::with_arguments(
+                1,
+                ArgumentsExtractor::new(arguments),
+                Vec::new(),
+                OneOffRootBTNode::new(
+                    2, PrintLogActionNode::new(3, "Parametrized.".to_owned()).into()).into())]);
+        let agent_service = &services.agent_service;
+
+        let build_agent = |arguments|
+            agent_service.build_new_agent(
+                AgentDefinition::new(
+                    arguments,
+                    HashMap::new(),
+                    ReactiveErrorPolicy::default(),
+                    RestartPolicy::default(),
+                    1,
+                    Some(1))).unwrap();
+
+        let plain_agent_id = build_agent(None);
+        let agent_id = build_agent(Some(json!({"recipient": "ops@example.com"})));
+
+        assert!(matches!(agent_service.migrate_agent(&plain_agent_id, &Some(2)),
+                         Err(AgentServiceError::InvalidArguments(_))));
+        assert_eq!(1, *agent_service.get_agent_status(&plain_agent_id).unwrap().get_tree_version());
+
+        assert_eq!(Ok(None), agent_service.get_value(&agent_id, &"recipient".to_owned()));
+        assert_eq!(Ok(2), agent_service.migrate_agent(&agent_id, &Some(2)));
+        assert_eq!(Ok(Some(ValueHolder::Email(Email::new("ops@example.com").unwrap()))),
+                   agent_service.get_value(&agent_id, &"recipient".to_owned()));
+    }
+
+    #[test]
+    fn test_lists_agents_matching_filter_page_by_page() {
+        let services = TestServices::new(
+            (1..3).map(|tree_id| print_log_tree(tree_id, "Listed.")).collect());
+        let agent_service = &services.agent_service;
+
+        let mut labels = HashMap::new();
+        labels.insert("env".to_owned(), "prod".to_owned());
+
+        let build_agent = |tree_id, labels|
+            agent_service
+                .build_new_agent(
+                    AgentDefinition::new(
+                        None,
+                        labels,
+                        ReactiveErrorPolicy::default(),
+                        RestartPolicy::default(),
+                        tree_id,
+                        None))
+                .unwrap();
+
+        let mut agent_ids: Vec<Uuid> = (0..3)
+            .map(|_| build_agent(1, labels.clone()))
+            .collect();
+        build_agent(2, labels.clone());
+        build_agent(1, HashMap::new());
+
+        let filter = AgentFilter::new(None, None, labels, None, Some(1));
+
+        let first_page = agent_service.list_agents(&filter, &Pagination::new(0, 2));
+        let second_page = agent_service.list_agents(&filter, &Pagination::new(2, 2));
+
+        assert_eq!(3, *first_page.get_total());
+        assert_eq!(2, first_page.get_agents().len());
+        assert_eq!(1, second_page.get_agents().len());
+
+        let mut listed_ids: Vec<Uuid> = first_page.get_agents()
+            .iter()
+            .chain(second_page.get_agents())
+            .map(|summary| *summary.get_id())
+            .collect();
+
+        agent_ids.sort();
+        listed_ids.sort();
+
+        assert_eq!(agent_ids, listed_ids);
+        assert_eq!(
+            0,
+            *agent_service.list_agents(
+                &AgentFilter::new(None, None, HashMap::new(), Some(AgentState::Running), None),
+                &Pagination::default()).get_total());
+    }
+
+    #[test]
+    fn test_restores_persisted_schedules_with_their_agents() {
+        let path = std::env::temp_dir().join(format!("schedules-{}.json", Uuid::new_v4()));
+        let build_services = |trees|
+            TestServices::with_schedule_store(ScheduleStore::new(path.clone()), trees);
+        let state = "state".to_owned();
+
+        let services = build_services(
+            vec![print_log_tree(1, "Scheduled."), print_log_tree(2, "Dropped.")]);
+        let agent_service = &services.agent_service;
+        let agent_id = agent_service.build_new_agent(1.into()).unwrap();
+        let schedule_id = agent_service
+            .schedule_agent(
+                &agent_id,
+                ScheduleDefinition::Cron("0 3 * * *".to_owned(), TzWrapper::new(Tz::UTC)))
+            .unwrap();
+
+        agent_service.get_agent(&agent_id)
+            .unwrap()
+            .get_context()
+            .get_context()
+            .put_values(&ValuesPayload::singleton(state.clone(), "kept".into()))
+            .unwrap();
+
+        assert!(agent_service
+            .schedule_agent(
+                &agent_id,
+                ScheduleDefinition::Cron("0 3".to_owned(), TzWrapper::new(Tz::UTC)))
+            .is_err());
+
+        let skipped_agent_id = agent_service.build_new_agent(2.into()).unwrap();
+        let skipped_blackboard_id = agent_service.get_blackboard_id(&skipped_agent_id).unwrap();
+
+        agent_service
+            .schedule_agent(
+                &skipped_agent_id,
+                ScheduleDefinition::Cron("0 4 * * *".to_owned(), TzWrapper::new(Tz::UTC)))
+            .unwrap();
+
+        services.shut_down();
+
+        let restored_services = build_services(vec![print_log_tree(1, "Scheduled.")]);
+        let restored_service = &restored_services.agent_service;
+
+        assert_eq!(Ok(1), restored_service.restore_schedules());
+        assert_eq!(vec![schedule_id],
+                   restored_service.get_schedules(&agent_id)
+                       .unwrap()
+                       .iter()
+                       .map(|schedule| *schedule.get_id())
+                       .collect::<Vec<Uuid>>());
+        assert_eq!(Ok(Some("kept".into())), restored_service.get_value(&agent_id, &state));
+
+        restored_service.delete_schedule(&agent_id, &schedule_id).unwrap();
+
+        assert_eq!(Ok(0), build_services(Vec::new()).agent_service.restore_schedules());
+
+        test_utils::destroy(format!("{}.bb", skipped_blackboard_id).into());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_removes_one_shot_schedule_once_fired() {
+        let path = std::env::temp_dir().join(format!("schedules-{}.json", Uuid::new_v4()));
+        let services = TestServices::with_schedule_store(
+            ScheduleStore::new(path.clone()), vec![print_log_tree(1, "Fired.")]);
+        let agent_service = &services.agent_service;
+        let agent_id = agent_service.build_new_agent(1.into()).unwrap();
+        let now = agent_service.get_agent(&agent_id).unwrap().now();
+
+        assert_eq!(
+            Err(AgentServiceError::SchedulingError(SchedulingError::StartInPast)),
+            agent_service.schedule_agent(
+                &agent_id, ScheduleDefinition::At(ZonedDateTime::new(now, Tz::UTC))));
+
+        agent_service
+            .schedule_agent(
+                &agent_id,
+                ScheduleDefinition::At(
+                    ZonedDateTime::new(now + chrono::Duration::milliseconds(50), Tz::UTC)))
+            .unwrap();
+
+        let store = ScheduleStore::new(path.clone());
+
+        for _ in 0..100 {
+            if agent_service.get_schedules(&agent_id).unwrap().is_empty()
+                && store.load().unwrap().is_empty() {
+                break;
+            }
+
+            std::thread::sleep(Duration::from_millis(20));
+        }
+
+        assert!(agent_service.get_schedules(&agent_id).unwrap().is_empty());
+        assert!(store.load().unwrap().is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn print_log_tree(tree_id: i32,
+                      message: &str) -> BehaviorTree {
+        BehaviorTree::new(
+            tree_id,
+            OneOffRootBTNode::new(
+                2, PrintLogActionNode::new(3, message.to_owned()).into()).into())
+    }
+
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use buttercup_bts::tick::TickStatus;
+
+use crate::AgentError;
+
+///
+/// Number of the most recent restarts kept per agent.
+///
+pub const KEPT_RESTARTS_COUNT: usize = 10;
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone, Copy, Default)]
+pub enum RestartMode {
+
+    Always,
+    #[default]
+    Never,
+    OnFailure
+
+}
+
+///
+/// Decides whether an agent is started again once its execution ends, waiting
+/// for an exponentially growing backoff before each restart. Agents stopped
+/// on request are never restarted.
+///
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+#[serde(default)]
+pub struct RestartPolicy {
+
+    initial_backoff_ms: u64,
+    max_backoff_ms: u64,
+    max_restarts: Option<u32>,
+    mode: RestartMode
+
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RestartRecord {
+
+    attempt: u32,
+    backoff_ms: u64,
+    previous_result_id: Uuid,
+    restarted_at_utc: NaiveDateTime
+
+}
+
+impl RestartPolicy {
+
+    pub fn new(initial_backoff_ms: u64,
+               max_backoff_ms: u64,
+               max_restarts: Option<u32>,
+               mode: RestartMode) -> RestartPolicy {
+        RestartPolicy {
+            initial_backoff_ms,
+            max_backoff_ms,
+            max_restarts,
+            mode
+        }
+    }
+
+    ///
+    /// Returns the backoff to wait for before the restart, none if the agent should not be
+    /// restarted after the given number of restarts.
+    ///
+    pub fn get_backoff(&self,
+                       result: &Result<TickStatus, AgentError>,
+                       restarts_count: u32) -> Option<Duration> {
+        if self.max_restarts.is_some_and(|max_restarts| restarts_count >= max_restarts) {
+            return None;
+        }
+
+        let restart = match (&self.mode, result) {
+            (_, Err(AgentError::AbortedError(_))) | (RestartMode::Never, _) => false,
+            (RestartMode::Always, _) => true,
+            (RestartMode::OnFailure, Ok(TickStatus::Success)) => false,
+            (RestartMode::OnFailure, _) => true
+        };
+
+        if !restart {
+            return None;
+        }
+
+        let backoff_ms = 2u64
+            .checked_pow(restarts_count)
+            .and_then(|multiplier| self.initial_backoff_ms.checked_mul(multiplier))
+            .unwrap_or(u64::MAX)
+            .min(self.max_backoff_ms);
+
+        Some(Duration::from_millis(backoff_ms))
+    }
+
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy::new(1000, 60000, None, RestartMode::Never)
+    }
+}
+
+impl RestartRecord {
+
+    pub fn new(attempt: u32,
+               backoff_ms: u64,
+               previous_result_id: Uuid,
+               restarted_at_utc: NaiveDateTime) -> RestartRecord {
+        RestartRecord {
+            attempt,
+            backoff_ms,
+            previous_result_id,
+            restarted_at_utc
+        }
+    }
+
+    pub fn get_attempt(&self) -> &u32 {
+        &self.attempt
+    }
+
+    pub fn get_backoff_ms(&self) -> &u64 {
+        &self.backoff_ms
+    }
+
+    pub fn get_previous_result_id(&self) -> &Uuid {
+        &self.previous_result_id
+    }
+
+    pub fn get_restarted_at_utc(&self) -> &NaiveDateTime {
+        &self.restarted_at_utc
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backs_off_exponentially_up_to_max_restarts() {
+        let policy = RestartPolicy::new(100, 1000, Some(5), RestartMode::OnFailure);
+        let failure = Result::Ok(TickStatus::Failure);
+
+        assert_eq!(Some(Duration::from_millis(100)), policy.get_backoff(&failure, 0));
+        assert_eq!(Some(Duration::from_millis(400)), policy.get_backoff(&failure, 2));
+        assert_eq!(Some(Duration::from_millis(1000)), policy.get_backoff(&failure, 4));
+        assert_eq!(None, policy.get_backoff(&failure, 5));
+        assert_eq!(None, policy.get_backoff(&Result::Ok(TickStatus::Success), 0));
+        assert_eq!(None,
+                   policy.get_backoff(&Result::Err(AgentError::AbortedError("".to_owned())), 0));
+    }
+
+}
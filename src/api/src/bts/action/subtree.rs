@@ -1,14 +1,20 @@
 use std::collections::HashSet;
 
+use serde::{Deserialize, Serialize};
+
 use buttercup_bts::node::action::subtree::ExecuteSubTreeActionNode;
 use buttercup_bts::node::BTNode;
 
 use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeDefinitionService, BehaviorTreeNodeDefinition};
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ExecuteSubTreeActionNodeDefinition {
 
     id: i32,
-    tree_id: i32
+    tree_id: i32,
+
+    #[serde(default)]
+    version: Option<u32>
 
 }
 
@@ -16,18 +22,39 @@ impl ExecuteSubTreeActionNodeDefinition {
 
     pub fn new(id: i32,
                tree_id: i32) -> ExecuteSubTreeActionNodeDefinition {
+        ExecuteSubTreeActionNodeDefinition::with_version(id, tree_id, None)
+    }
+
+    ///
+    /// Executes the given version of the subtree, the latest one at the time of building if none.
+    ///
+    pub fn with_version(id: i32,
+                        tree_id: i32,
+                        version: Option<u32>) -> ExecuteSubTreeActionNodeDefinition {
         ExecuteSubTreeActionNodeDefinition {
             id,
-            tree_id
+            tree_id,
+            version
         }
     }
 
+    pub fn get_tree_id(&self) -> &i32 {
+        &self.tree_id
+    }
+
+    pub fn get_version(&self) -> &Option<u32> {
+        &self.version
+    }
+
 }
 
 impl BehaviorTreeNodeDefinition for ExecuteSubTreeActionNodeDefinition {
     fn build(&self,
              context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
-        let subtree = context.get_subtree(&self.tree_id)?;
+        let subtree = match &self.version {
+            None => context.get_subtree(&self.tree_id)?,
+            Some(version) => context.get_subtree_version(&self.tree_id, version)?
+        };
 
         Result::Ok(ExecuteSubTreeActionNode::new(self.id, subtree)?.into())
     }
@@ -39,6 +66,10 @@ impl BehaviorTreeNodeDefinition for ExecuteSubTreeActionNodeDefinition {
     fn get_subtree_ids(&self,
                        service: &BehaviorTreeDefinitionService)
                        -> Result<HashSet<i32>, BehaviorTreeBuildingError> {
+        if self.version.is_some() {
+            return Result::Ok(HashSet::new());
+        }
+
         match service.get(&self.tree_id) {
             None => Result::Err(BehaviorTreeBuildingError::CouldNotFindSubtreeWithId(self.tree_id)),
 
@@ -52,6 +83,13 @@ impl BehaviorTreeNodeDefinition for ExecuteSubTreeActionNodeDefinition {
 
         }
     }
+
+    fn get_pinned_subtrees(&self) -> HashSet<(i32, u32)> {
+        self.version
+            .iter()
+            .map(|version| (self.tree_id, *version))
+            .collect()
+    }
 }
 
 impl From<()> for BehaviorTreeBuildingError {
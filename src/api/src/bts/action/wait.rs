@@ -1,11 +1,15 @@
 use std::time::Duration;
 
-use buttercup_bts::node::action::wait::WaitDurationActionNode;
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::node::action::wait::{WaitDurationActionNode, WaitUntilActionNode};
 use buttercup_bts::node::BTNode;
+use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper};
 use buttercup_variables::VariableSpecification;
 
 use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct WaitDurationActionNodeDefinition {
 
     id: i32,
@@ -26,3 +30,29 @@ impl BehaviorTreeNodeDefinition for WaitDurationActionNodeDefinition {
         &self.id
     }
 }
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WaitUntilActionNodeDefinition {
+
+    id: i32,
+    expression: ConditionExpression,
+    timeout: VariableSpecification<Duration>
+
+}
+
+impl BehaviorTreeNodeDefinition for WaitUntilActionNodeDefinition {
+    fn build(&self,
+             _: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            WaitUntilActionNode::new(
+                self.id,
+                ConditionExpressionWrapper::new(self.expression.clone()),
+                self.timeout.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -1,8 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use buttercup_bts::node::BTNode;
 use buttercup_bts::node::composite::fallback::FallbackCompositeNode;
 
 use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct FallbackCompositeNodeDefinition {
 
     id: i32,
@@ -1,3 +1,6 @@
 pub mod fallback;
 pub mod parallel;
-pub mod sequence;
\ No newline at end of file
+pub mod priority;
+pub mod reactive;
+pub mod sequence;
+pub mod utility;
\ No newline at end of file
@@ -1,8 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use buttercup_bts::node::BTNode;
 use buttercup_bts::node::composite::parallel::{ParallelCompositeNode, ParallelCompositeNodeBuildingError};
 
 use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ParallelCompositeNodeDefinition {
 
     id: i32,
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::composite::priority::{PrioritizedChild, PrioritySelectorCompositeNode};
+use buttercup_variables::VariableSpecification;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PrioritySelectorCompositeNodeDefinition {
+
+    id: i32,
+    children: Vec<PrioritizedChildDefinition>
+
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PrioritizedChildDefinition {
+
+    child_id: i32,
+    priority: VariableSpecification<i64>
+
+}
+
+impl PrioritizedChildDefinition {
+
+    pub fn new(child_id: i32,
+               priority: VariableSpecification<i64>) -> PrioritizedChildDefinition {
+        PrioritizedChildDefinition {
+            child_id,
+            priority
+        }
+    }
+
+}
+
+impl PrioritySelectorCompositeNodeDefinition {
+
+    pub fn new(id: i32,
+               children: Vec<PrioritizedChildDefinition>) -> PrioritySelectorCompositeNodeDefinition {
+        PrioritySelectorCompositeNodeDefinition {
+            id,
+            children
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for PrioritySelectorCompositeNodeDefinition {
+    fn build(&self, context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        let mut children = Vec::with_capacity(self.children.len());
+
+        for child in &self.children {
+            children.push(
+                PrioritizedChild::new(
+                    child.priority.clone(),
+                    context.build_child(&child.child_id)?));
+        }
+
+        Ok(
+            PrioritySelectorCompositeNode::new(self.id, children)
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::composite::reactive::{ReactiveFallbackCompositeNode, ReactiveSequenceCompositeNode};
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReactiveSequenceCompositeNodeDefinition {
+
+    id: i32,
+    children_ids: Vec<i32>
+
+}
+
+impl ReactiveSequenceCompositeNodeDefinition {
+
+    pub fn new(id: i32,
+               children_ids: Vec<i32>) -> ReactiveSequenceCompositeNodeDefinition {
+        ReactiveSequenceCompositeNodeDefinition {
+            id,
+            children_ids
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for ReactiveSequenceCompositeNodeDefinition {
+    fn build(&self, context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Ok(
+            ReactiveSequenceCompositeNode::new(
+                self.id,
+                context.build_children(&self.children_ids)?)
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReactiveFallbackCompositeNodeDefinition {
+
+    id: i32,
+    children_ids: Vec<i32>
+
+}
+
+impl ReactiveFallbackCompositeNodeDefinition {
+
+    pub fn new(id: i32,
+               children_ids: Vec<i32>) -> ReactiveFallbackCompositeNodeDefinition {
+        ReactiveFallbackCompositeNodeDefinition {
+            id,
+            children_ids
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for ReactiveFallbackCompositeNodeDefinition {
+    fn build(&self, context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Ok(
+            ReactiveFallbackCompositeNode::new(
+                self.id,
+                context.build_children(&self.children_ids)?)
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
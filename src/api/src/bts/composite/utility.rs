@@ -0,0 +1,80 @@
+use num::BigRational;
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::composite::utility::{ScoredChild, UtilitySelectorCompositeNode, UtilityTieBreaking};
+use buttercup_conditions::arithmetic::ArithmeticExpression;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UtilitySelectorCompositeNodeDefinition {
+
+    id: i32,
+    children: Vec<ScoredChildDefinition>,
+    hysteresis: Option<BigRational>,
+    tie_breaking: UtilityTieBreaking
+
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ScoredChildDefinition {
+
+    child_id: i32,
+    score: ArithmeticExpression
+
+}
+
+impl ScoredChildDefinition {
+
+    pub fn new(child_id: i32,
+               score: ArithmeticExpression) -> ScoredChildDefinition {
+        ScoredChildDefinition {
+            child_id,
+            score
+        }
+    }
+
+}
+
+impl UtilitySelectorCompositeNodeDefinition {
+
+    pub fn new(id: i32,
+               children: Vec<ScoredChildDefinition>,
+               hysteresis: Option<BigRational>,
+               tie_breaking: UtilityTieBreaking) -> UtilitySelectorCompositeNodeDefinition {
+        UtilitySelectorCompositeNodeDefinition {
+            id,
+            children,
+            hysteresis,
+            tie_breaking
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for UtilitySelectorCompositeNodeDefinition {
+    fn build(&self, context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        let mut children = Vec::with_capacity(self.children.len());
+
+        for child in &self.children {
+            children.push(
+                ScoredChild::new(
+                    context.build_child(&child.child_id)?,
+                    child.score.clone()));
+        }
+
+        Ok(
+            UtilitySelectorCompositeNode::new(
+                self.id,
+                children,
+                self.hysteresis.clone(),
+                self.tie_breaking.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
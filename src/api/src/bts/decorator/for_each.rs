@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::for_each::{ForEachDecoratorNode, ForEachFailurePolicy};
+use buttercup_variables::VariableName;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ForEachDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    element_key: String,
+    failure_policy: ForEachFailurePolicy,
+    index_key: String,
+    list_name: VariableName
+
+}
+
+impl ForEachDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               list_name: VariableName,
+               element_key: String,
+               index_key: String,
+               failure_policy: ForEachFailurePolicy) -> ForEachDecoratorNodeDefinition {
+        ForEachDecoratorNodeDefinition {
+            id,
+            child_id,
+            element_key,
+            failure_policy,
+            index_key,
+            list_name
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for ForEachDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Result::Ok(
+            ForEachDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?.into(),
+                self.list_name.clone(),
+                self.element_key.clone(),
+                self.index_key.clone(),
+                self.failure_policy.clone())
+                .into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
@@ -1,7 +1,9 @@
 use crate::bts::{BehaviorTreeNodeDefinition, BehaviorTreeBuildingContext, BehaviorTreeBuildingError};
 use buttercup_bts::node::BTNode;
 use buttercup_bts::node::decorator::invert::InvertDecoratorNode;
+use serde::{Deserialize, Serialize};
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct InvertDecoratorNodeDefinition {
 
     id: i32,
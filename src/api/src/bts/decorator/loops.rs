@@ -0,0 +1,105 @@
+use num::BigInt;
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::decorator::loops::{ForDecoratorNode, WhileDecoratorNode};
+use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper};
+use buttercup_variables::VariableSpecification;
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeNodeDefinition};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WhileDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    expression: ConditionExpression,
+    index_key: String
+
+}
+
+impl WhileDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               expression: ConditionExpression,
+               index_key: String) -> WhileDecoratorNodeDefinition {
+        WhileDecoratorNodeDefinition {
+            id,
+            child_id,
+            expression,
+            index_key
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for WhileDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Ok(
+            WhileDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?.into(),
+                ConditionExpressionWrapper::new(self.expression.clone()),
+                self.index_key.clone()
+            ).into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ForDecoratorNodeDefinition {
+
+    id: i32,
+    child_id: i32,
+    from: VariableSpecification<BigInt>,
+    index_key: String,
+    step: VariableSpecification<BigInt>,
+    to: VariableSpecification<BigInt>
+
+}
+
+impl ForDecoratorNodeDefinition {
+
+    pub fn new(id: i32,
+               child_id: i32,
+               from: VariableSpecification<BigInt>,
+               to: VariableSpecification<BigInt>,
+               step: VariableSpecification<BigInt>,
+               index_key: String) -> ForDecoratorNodeDefinition {
+        ForDecoratorNodeDefinition {
+            id,
+            child_id,
+            from,
+            index_key,
+            step,
+            to
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for ForDecoratorNodeDefinition {
+    fn build(&self,
+             ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        Ok(
+            ForDecoratorNode::new(
+                self.id,
+                ctx.build_child(&self.child_id)?.into(),
+                self.from.clone(),
+                self.to.clone(),
+                self.step.clone(),
+                self.index_key.clone()
+            ).into()
+        )
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
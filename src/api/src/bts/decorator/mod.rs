@@ -1,3 +1,5 @@
 pub mod condition;
+pub mod for_each;
 pub mod invert;
+pub mod loops;
 pub mod reactive;
\ No newline at end of file
@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use dashmap::DashMap;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::tree::{BehaviorTree, BehaviorTreeService, BehaviorTreeServiceError};
+
+use crate::bts::{BehaviorTreeBuildingError, BehaviorTreeBuildingService, BehaviorTreeDefinitionService};
+use crate::bts::serialized::SerializedBehaviorTreeDefinition;
+
+///
+/// Keeps the latest tree definitions managed through the API, each one is validated
+/// by building the tree, which is then registered in the tree service as its next version.
+/// Trees using another one as a subtree keep the version they were built with.
+/// Changes are persisted in the store, so the same versions can be restored.
+///
+pub struct BehaviorTreeManagementService {
+
+    behavior_tree_service: Arc<BehaviorTreeService>,
+    building_service: BehaviorTreeBuildingService,
+    changes: Mutex<Vec<BehaviorTreeChange>>,
+    definition_service: Arc<BehaviorTreeDefinitionService>,
+    definitions: DashMap<i32, SerializedBehaviorTreeDefinition>,
+    lock: Mutex<()>,
+    store: BehaviorTreeStore
+
+}
+
+///
+/// Registered versions and deletions of trees, replayed in order they were made,
+/// so that subtrees resolve to the same versions as before.
+///
+#[derive(Serialize, Deserialize, Clone)]
+pub enum BehaviorTreeChange {
+
+    Deleted(i32),
+    Registered(u32, SerializedBehaviorTreeDefinition)
+
+}
+
+///
+/// Keeps the tree changes in a JSON file, if given a path.
+///
+#[derive(Default)]
+pub struct BehaviorTreeStore {
+
+    path: Option<PathBuf>,
+    lock: Mutex<()>
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum BehaviorTreeManagementError {
+
+    BuildingError(BehaviorTreeBuildingError),
+    CyclicSubtreeReference(i32),
+    IOError(String),
+    MismatchedTreeId(i32),
+    SerializationError(String),
+    TreeIsUsedAsSubtree(i32),
+    TreeOfGivenIdAlreadyExists(i32),
+    TreeOfGivenIdNotFound(i32),
+    TreeVersionIsPinned(i32, u32)
+
+}
+
+impl BehaviorTreeManagementService {
+
+    pub fn new(behavior_tree_service: Arc<BehaviorTreeService>,
+               definition_service: Arc<BehaviorTreeDefinitionService>) -> BehaviorTreeManagementService {
+        BehaviorTreeManagementService::with_store(behavior_tree_service,
+                                                  definition_service,
+                                                  BehaviorTreeStore::default())
+    }
+
+    pub fn with_store(behavior_tree_service: Arc<BehaviorTreeService>,
+                      definition_service: Arc<BehaviorTreeDefinitionService>,
+                      store: BehaviorTreeStore) -> BehaviorTreeManagementService {
+        BehaviorTreeManagementService {
+            behavior_tree_service: behavior_tree_service.clone(),
+            building_service: BehaviorTreeBuildingService::new(behavior_tree_service,
+                                                               definition_service.clone()),
+            changes: Mutex::new(Vec::new()),
+            definition_service,
+            definitions: DashMap::new(),
+            lock: Mutex::new(()),
+            store
+        }
+    }
+
+    ///
+    /// Replays the persisted changes, rebuilding each tree under its version. Versions which
+    /// cannot be built are logged and skipped. Returns the number of the restored trees.
+    ///
+    pub fn restore(&self) -> Result<usize, BehaviorTreeManagementError> {
+        let _lock = self.lock.lock().unwrap();
+        let changes = self.store.load()?;
+
+        for change in &changes {
+            match change {
+                BehaviorTreeChange::Deleted(id) => {
+                    self.behavior_tree_service.remove(id)?;
+                    self.definitions.remove(id);
+                    self.definition_service.remove(id);
+                },
+                BehaviorTreeChange::Registered(version, definition) => {
+                    let id = *definition.get_id();
+
+                    self.behavior_tree_service.reserve_versions(&id, *version);
+
+                    match self.build(definition) {
+                        Ok(tree) => {
+                            self.behavior_tree_service.insert_version(tree, *version);
+                            self.definitions.insert(id, definition.clone());
+                        },
+                        Err(err) => warn!("Version {} of tree {} could not be restored: {:?}",
+                                          version, id, err)
+                    }
+                }
+            }
+        }
+
+        *self.changes.lock().unwrap() = changes;
+
+        Result::Ok(self.definitions.len())
+    }
+
+    pub fn create(&self,
+                  definition: SerializedBehaviorTreeDefinition) -> Result<(), BehaviorTreeManagementError> {
+        let _lock = self.lock.lock().unwrap();
+        let id = *definition.get_id();
+
+        if self.definitions.contains_key(&id) || self.behavior_tree_service.get_by_id(&id).is_some() {
+            return Result::Err(BehaviorTreeManagementError::TreeOfGivenIdAlreadyExists(id));
+        }
+
+        self.register(definition)
+    }
+
+    pub fn get(&self,
+               id: &i32) -> Option<SerializedBehaviorTreeDefinition> {
+        self.definitions.get(id).map(|definition| definition.clone())
+    }
+
+    ///
+    /// Returns the definitions ordered by id.
+    ///
+    pub fn list(&self) -> Vec<SerializedBehaviorTreeDefinition> {
+        let mut definitions: Vec<SerializedBehaviorTreeDefinition> = self.definitions
+            .iter()
+            .map(|definition| definition.clone())
+            .collect();
+
+        definitions.sort_by_key(|definition| *definition.get_id());
+
+        definitions
+    }
+
+    pub fn get_versions(&self,
+                        id: &i32) -> Vec<u32> {
+        self.behavior_tree_service.get_versions(id)
+    }
+
+    ///
+    /// Registers the definition as the next version of the tree, keeps the previous
+    /// one if the new one cannot be built.
+    ///
+    pub fn update(&self,
+                  id: &i32,
+                  definition: SerializedBehaviorTreeDefinition) -> Result<(), BehaviorTreeManagementError> {
+        let _lock = self.lock.lock().unwrap();
+
+        if definition.get_id() != id {
+            return Result::Err(BehaviorTreeManagementError::MismatchedTreeId(*definition.get_id()));
+        }
+
+        if !self.definitions.contains_key(id) {
+            return Result::Err(BehaviorTreeManagementError::TreeOfGivenIdNotFound(*id));
+        }
+
+        self.register(definition)
+    }
+
+    ///
+    /// Removes all versions of the tree, unless agents still execute any of them.
+    /// Versions of the tree are not reused once it is created again.
+    ///
+    pub fn delete(&self,
+                  id: &i32) -> Result<(), BehaviorTreeManagementError> {
+        let _lock = self.lock.lock().unwrap();
+
+        if !self.definitions.contains_key(id) {
+            return Result::Err(BehaviorTreeManagementError::TreeOfGivenIdNotFound(*id));
+        }
+
+        if let Some(user) = self.definitions
+            .iter()
+            .find(|definition|
+                definition.get_id() != id && definition.get_direct_subtree_ids().contains(id)) {
+            return Result::Err(BehaviorTreeManagementError::TreeIsUsedAsSubtree(*user.get_id()));
+        }
+
+        self.behavior_tree_service.remove(id)?;
+        self.definitions.remove(id);
+        self.definition_service.remove(id);
+
+        self.persist(BehaviorTreeChange::Deleted(*id))
+    }
+
+    fn register(&self,
+                definition: SerializedBehaviorTreeDefinition) -> Result<(), BehaviorTreeManagementError> {
+        let id = *definition.get_id();
+
+        self.check_subtree_cycles(&definition)?;
+
+        let tree = self.behavior_tree_service.insert(self.build(&definition)?);
+        self.definitions.insert(id, definition.clone());
+
+        self.persist(BehaviorTreeChange::Registered(*tree.get_version(), definition))
+    }
+
+    ///
+    /// The tree which was already registered stays available until restart,
+    /// even if the change could not be persisted.
+    ///
+    fn persist(&self,
+               change: BehaviorTreeChange) -> Result<(), BehaviorTreeManagementError> {
+        let mut changes = self.changes.lock().unwrap();
+
+        changes.push(change);
+
+        self.store.save(&changes)
+    }
+
+    ///
+    /// Builds the tree of the definition, keeps the previous definition if it cannot be built.
+    ///
+    fn build(&self,
+             definition: &SerializedBehaviorTreeDefinition) -> Result<BehaviorTree, BehaviorTreeManagementError> {
+        let id = *definition.get_id();
+        let previous = self.definition_service.insert(definition.to_definition());
+
+        match self.building_service.build(&id) {
+            Ok(tree) => Result::Ok(tree),
+            Err(err) => {
+                match previous {
+                    Some(previous) => {
+                        self.definition_service.insert(previous);
+                    },
+                    None => {
+                        self.definition_service.remove(&id);
+                    }
+                }
+
+                Result::Err(err.into())
+            }
+        }
+    }
+
+    ///
+    /// Stored definitions have no cycles, so a new one could only go through the given tree.
+    /// Pinned versions are already built, so they cannot form one.
+    ///
+    fn check_subtree_cycles(&self,
+                            definition: &SerializedBehaviorTreeDefinition)
+        -> Result<(), BehaviorTreeManagementError> {
+        let mut visited = HashSet::new();
+        let mut pending: Vec<i32> = definition.get_latest_subtree_ids().into_iter().collect();
+
+        while let Some(subtree_id) = pending.pop() {
+            if subtree_id == *definition.get_id() {
+                return Result::Err(
+                    BehaviorTreeManagementError::CyclicSubtreeReference(subtree_id));
+            }
+
+            if visited.insert(subtree_id) {
+                if let Some(subtree) = self.definitions.get(&subtree_id) {
+                    pending.extend(subtree.get_latest_subtree_ids());
+                }
+            }
+        }
+
+        Result::Ok(())
+    }
+
+}
+
+impl BehaviorTreeStore {
+
+    pub fn new(path: PathBuf) -> BehaviorTreeStore {
+        BehaviorTreeStore {
+            path: Some(path),
+            lock: Mutex::new(())
+        }
+    }
+
+    ///
+    /// Returns no changes if the file does not exist yet.
+    ///
+    pub fn load(&self) -> Result<Vec<BehaviorTreeChange>, BehaviorTreeManagementError> {
+        let _lock = self.lock.lock().unwrap();
+
+        match &self.path {
+            Some(path) if path.exists() => {
+                let content = fs::read_to_string(path)?;
+
+                serde_json::from_str(&content)
+                    .map_err(|err| BehaviorTreeManagementError::SerializationError(err.to_string()))
+            },
+            _ => Result::Ok(Vec::new())
+        }
+    }
+
+    pub fn save(&self,
+                changes: &[BehaviorTreeChange]) -> Result<(), BehaviorTreeManagementError> {
+        let _lock = self.lock.lock().unwrap();
+
+        if let Some(path) = &self.path {
+            let content = serde_json::to_string(changes)
+                .map_err(|err| BehaviorTreeManagementError::SerializationError(err.to_string()))?;
+
+            fs::write(path, content)?;
+        }
+
+        Result::Ok(())
+    }
+
+}
+
+impl From<std::io::Error> for BehaviorTreeManagementError {
+    fn from(err: std::io::Error) -> Self {
+        BehaviorTreeManagementError::IOError(err.to_string())
+    }
+}
+
+impl From<BehaviorTreeBuildingError> for BehaviorTreeManagementError {
+    fn from(err: BehaviorTreeBuildingError) -> Self {
+        BehaviorTreeManagementError::BuildingError(err)
+    }
+}
+
+impl From<BehaviorTreeServiceError> for BehaviorTreeManagementError {
+    fn from(err: BehaviorTreeServiceError) -> Self {
+        match err {
+            BehaviorTreeServiceError::TreeVersionIsPinned(id, version) =>
+                BehaviorTreeManagementError::TreeVersionIsPinned(id, version)
+        }
+    }
+}
@@ -4,16 +4,20 @@ use std::sync::Arc;
 
 use dashmap::DashMap;
 use dashmap::mapref::one::Ref;
+use serde::{Deserialize, Serialize};
 
 use buttercup_bts::node::BTNode;
 use buttercup_bts::tree::{BehaviorTree, BehaviorTreeService};
+use buttercup_endpoints::{ArgumentsExtractor, ArgumentSetDefinition};
 
 use crate::bts::root::RootBTNodeDefinition;
 
 pub mod action;
 pub mod composite;
 pub mod decorator;
+pub mod management;
 pub mod root;
+pub mod serialized;
 
 #[derive(Default)]
 pub struct BehaviorTreeDefinitionService {
@@ -29,8 +33,13 @@ impl BehaviorTreeDefinitionService {
         self.definitions.get(id)
     }
 
-    pub fn insert(&self, definition: BehaviorTreeDefinition) {
-        self.definitions.insert(definition.id, definition);
+    pub fn insert(&self, definition: BehaviorTreeDefinition) -> Option<BehaviorTreeDefinition> {
+        self.definitions.insert(definition.id, definition)
+    }
+
+    pub fn remove(&self,
+                  id: &i32) -> Option<BehaviorTreeDefinition> {
+        self.definitions.remove(id).map(|(_, definition)| definition)
     }
 
 }
@@ -38,7 +47,9 @@ impl BehaviorTreeDefinitionService {
 pub struct BehaviorTreeDefinition {
 
     id: i32,
+    arguments: ArgumentsExtractor,
     definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
+    endpoints: Vec<ArgumentSetDefinition>,
     root_node: Box<dyn RootBTNodeDefinition>
 
 }
@@ -47,7 +58,11 @@ impl BehaviorTreeDefinition {
 
     pub fn build(&self,
                  context: &BehaviorTreeBuildingContext) -> Result<BehaviorTree, BehaviorTreeBuildingError> {
-        Result::Ok(BehaviorTree::new(self.id, self.root_node.build(&context)?))
+        Result::Ok(
+            BehaviorTree::with_arguments(self.id,
+                                         self.arguments.clone(),
+                                         self.endpoints.clone(),
+                                         self.root_node.build(context)?))
     }
 
     pub fn get_id(&self) -> &i32 {
@@ -74,19 +89,37 @@ impl BehaviorTreeDefinition {
         Result::Ok(ids)
     }
 
+    pub fn get_pinned_subtrees(&self) -> HashSet<(i32, u32)> {
+        self.get_definitions()
+            .iter()
+            .flat_map(|node_definition| node_definition.get_pinned_subtrees())
+            .collect()
+    }
+
     pub fn new(id: i32,
                definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
                root_node: Box<dyn RootBTNodeDefinition>) -> BehaviorTreeDefinition {
+        BehaviorTreeDefinition::with_arguments(
+            id, ArgumentsExtractor::default(), definitions, Vec::new(), root_node)
+    }
+
+    pub fn with_arguments(id: i32,
+                          arguments: ArgumentsExtractor,
+                          definitions: Vec<Arc<dyn BehaviorTreeNodeDefinition>>,
+                          endpoints: Vec<ArgumentSetDefinition>,
+                          root_node: Box<dyn RootBTNodeDefinition>) -> BehaviorTreeDefinition {
         BehaviorTreeDefinition {
             id,
+            arguments,
             definitions,
+            endpoints,
             root_node
         }
     }
 }
 
 
-pub trait BehaviorTreeNodeDefinition {
+pub trait BehaviorTreeNodeDefinition: Send + Sync {
 
     fn build(&self,
              ctx: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError>;
@@ -99,6 +132,13 @@ pub trait BehaviorTreeNodeDefinition {
         Result::Ok(HashSet::new())
     }
 
+    ///
+    /// Versions of the subtrees referenced by the node, which are already built.
+    ///
+    fn get_pinned_subtrees(&self) -> HashSet<(i32, u32)> {
+        HashSet::new()
+    }
+
 }
 
 #[derive(Default)]
@@ -141,11 +181,7 @@ impl BehaviorTreeBuildingService {
 
         for subtree_id in subtree_ids {
             let subtree = match self.behavior_tree_service.get_by_id(&subtree_id) {
-                None => {
-                    let subtree = Arc::new(self.build(&subtree_id)?);
-                    self.behavior_tree_service.insert_arc(subtree.clone());
-                    subtree
-                },
+                None => self.behavior_tree_service.insert(self.build(&subtree_id)?),
 
                 Some(subtree) => subtree
             };
@@ -153,23 +189,35 @@ impl BehaviorTreeBuildingService {
             subtrees.insert(*subtree.get_id(), subtree);
         }
 
+        let mut pinned_subtrees = HashMap::new();
+
+        for (subtree_id, version) in tree_definition.get_pinned_subtrees() {
+            let subtree = self.behavior_tree_service
+                .get_version(&subtree_id, &version)
+                .ok_or(BehaviorTreeBuildingError::CouldNotFindSubtreeVersion(subtree_id, version))?;
+
+            pinned_subtrees.insert((subtree_id, version), subtree);
+        }
+
         Result::Ok(
-            BehaviorTreeBuildingContext::new(
+            BehaviorTreeBuildingContext::with_pinned_subtrees(
                 tree_definition.get_definitions()
                     .into_iter()
                     .map(|def| (*def.get_id(), def.clone()))
                     .collect(),
-                subtrees))
+                subtrees,
+                pinned_subtrees))
     }
 
 }
 
-#[derive(Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
 pub enum BehaviorTreeBuildingError {
 
     CouldNotFindChildDefinitionWithId(i32),
     CouldNotFindTreeWithId(i32),
     CouldNotFindSubtreeWithId(i32),
+    CouldNotFindSubtreeVersion(i32, u32),
     GotUnexpectedNodeType(i32),
     ParallelCompositeNodeBuildingError,
     ProvidedTreeCannotBeASubtreeError,
@@ -179,6 +227,7 @@ pub enum BehaviorTreeBuildingError {
 pub struct BehaviorTreeBuildingContext {
 
     node_definitions: HashMap<i32, Arc<dyn BehaviorTreeNodeDefinition>>,
+    pinned_subtrees: HashMap<(i32, u32), Arc<BehaviorTree>>,
     subtrees: HashMap<i32, Arc<BehaviorTree>>
 
 }
@@ -187,8 +236,20 @@ impl BehaviorTreeBuildingContext {
 
     pub fn new(node_definitions: HashMap<i32, Arc<dyn BehaviorTreeNodeDefinition>>,
                subtrees: HashMap<i32, Arc<BehaviorTree>>) -> BehaviorTreeBuildingContext {
+        BehaviorTreeBuildingContext::with_pinned_subtrees(node_definitions, subtrees, HashMap::new())
+    }
+
+    ///
+    /// Subtrees are the latest versions at the time of building, pinned subtrees
+    /// are given by their id and version.
+    ///
+    pub fn with_pinned_subtrees(node_definitions: HashMap<i32, Arc<dyn BehaviorTreeNodeDefinition>>,
+                                subtrees: HashMap<i32, Arc<BehaviorTree>>,
+                                pinned_subtrees: HashMap<(i32, u32), Arc<BehaviorTree>>)
+        -> BehaviorTreeBuildingContext {
         BehaviorTreeBuildingContext {
             node_definitions,
+            pinned_subtrees,
             subtrees
         }
     }
@@ -220,4 +281,13 @@ impl BehaviorTreeBuildingContext {
         }
     }
 
+    pub fn get_subtree_version(&self,
+                               id: &i32,
+                               version: &u32) -> Result<Arc<BehaviorTree>, BehaviorTreeBuildingError> {
+        match self.pinned_subtrees.get(&(*id, *version)) {
+            None => Result::Err(BehaviorTreeBuildingError::CouldNotFindSubtreeVersion(*id, *version)),
+            Some(tree) => Result::Ok(tree.clone())
+        }
+    }
+
 }
@@ -2,19 +2,21 @@ use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError};
 use buttercup_bts::node::root::RootBTNode;
 use buttercup_bts::node::root::one_off::OneOffRootBTNode;
 use buttercup_bts::node::{BTNode, BehaviorTreeNode};
-use buttercup_bts::node::decorator::reactive::ReactiveConditionDecoratorNode;
+use buttercup_bts::node::composite::CompositeBTNode;
 use buttercup_bts::node::decorator::DecoratorBTNode;
-use buttercup_bts::node::root::reactive::ReactiveRootBTNode;
+use buttercup_bts::node::root::reactive::{ReactiveRootBTNode, ReactiveRootRestartPolicy};
 use buttercup_bts::node::root::to_first::ToFirstErrorRootBTNode;
 use buttercup_bts::node::root::until_stopped::UntilStoppedRootBTNode;
+use serde::{Deserialize, Serialize};
 
-pub trait RootBTNodeDefinition {
+pub trait RootBTNodeDefinition: Send + Sync {
 
     fn build(&self,
              context: &BehaviorTreeBuildingContext) -> Result<RootBTNode, BehaviorTreeBuildingError>;
 
 }
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct OneOffRootBTNodeDefinition {
 
     id: i32,
@@ -43,27 +45,54 @@ impl RootBTNodeDefinition for OneOffRootBTNodeDefinition {
 }
 
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ReactiveRootBTNodeDefinition {
 
     id: i32,
     child_id: i32,
+    restart_policy: ReactiveRootRestartPolicy,
     stop_on_error: bool
 
 }
 
 impl ReactiveRootBTNodeDefinition {
 
-    fn get_reactive_node(bt_node: BTNode)
-                         -> Result<ReactiveConditionDecoratorNode, BehaviorTreeBuildingError> {
-        let node_id = bt_node.get_id();
-
-        match bt_node {
-            BTNode::Decorator(
-                DecoratorBTNode::ReactiveCondition(node)) =>
-                Result::Ok(node),
+    pub fn new(id: i32,
+               child_id: i32,
+               restart_policy: ReactiveRootRestartPolicy,
+               stop_on_error: bool) -> ReactiveRootBTNodeDefinition {
+        ReactiveRootBTNodeDefinition {
+            id,
+            child_id,
+            restart_policy,
+            stop_on_error
+        }
+    }
 
-            _ => Result::Err(BehaviorTreeBuildingError::GotUnexpectedNodeType(*node_id))
+    ///
+    /// Only reactive nodes can be restarted, when the root waits for the condition the child
+    /// has to provide one.
+    ///
+    fn get_reactive_node(&self,
+                         bt_node: BTNode) -> Result<BTNode, BehaviorTreeBuildingError> {
+        let node_id = *bt_node.get_id();
+
+        let is_reactive = matches!(
+            &bt_node,
+            BTNode::Decorator(DecoratorBTNode::ReactiveCondition(_)) |
+            BTNode::Composite(CompositeBTNode::ReactiveFallback(_)) |
+            BTNode::Composite(CompositeBTNode::ReactiveSequence(_)));
+
+        let is_conditional_if_needed = match self.restart_policy {
+            ReactiveRootRestartPolicy::RestartWhenConditionMet => bt_node.as_conditional().is_some(),
+            _ => true
+        };
+
+        if is_reactive && is_conditional_if_needed {
+            return Result::Ok(bt_node);
         }
+
+        Result::Err(BehaviorTreeBuildingError::GotUnexpectedNodeType(node_id))
     }
 
 }
@@ -75,12 +104,13 @@ impl RootBTNodeDefinition for ReactiveRootBTNodeDefinition {
             ReactiveRootBTNode::new(
                 self.id,
                 Box::new(
-                    ReactiveRootBTNodeDefinition::get_reactive_node(
-                        context.build_child(&self.child_id)?)?),
+                    self.get_reactive_node(context.build_child(&self.child_id)?)?),
+                self.restart_policy.clone(),
                 self.stop_on_error).into())
     }
 }
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ToFirstErrorRootBTNodeDefinition {
 
     id: i32,
@@ -96,6 +126,7 @@ impl RootBTNodeDefinition for ToFirstErrorRootBTNodeDefinition {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone)]
 pub struct UntilStoppedRootBTNodeDefinition {
 
     id: i32,
@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use buttercup_bts::node::BTNode;
+use buttercup_bts::node::root::RootBTNode;
+use buttercup_endpoints::{ArgumentsExtractor, ArgumentSetDefinition};
+
+use crate::bts::{BehaviorTreeBuildingContext, BehaviorTreeBuildingError, BehaviorTreeDefinition, BehaviorTreeDefinitionService, BehaviorTreeNodeDefinition};
+use crate::bts::action::logging::PrintLogActionNodeDefinition;
+use crate::bts::action::subtree::ExecuteSubTreeActionNodeDefinition;
+use crate::bts::action::wait::{WaitDurationActionNodeDefinition, WaitUntilActionNodeDefinition};
+use crate::bts::composite::fallback::FallbackCompositeNodeDefinition;
+use crate::bts::composite::parallel::ParallelCompositeNodeDefinition;
+use crate::bts::composite::priority::PrioritySelectorCompositeNodeDefinition;
+use crate::bts::composite::reactive::{ReactiveFallbackCompositeNodeDefinition, ReactiveSequenceCompositeNodeDefinition};
+use crate::bts::composite::sequence::SequenceCompositeNodeDefinition;
+use crate::bts::composite::utility::UtilitySelectorCompositeNodeDefinition;
+use crate::bts::decorator::condition::ConditionDecoratorNodeDefinition;
+use crate::bts::decorator::for_each::ForEachDecoratorNodeDefinition;
+use crate::bts::decorator::invert::InvertDecoratorNodeDefinition;
+use crate::bts::decorator::loops::{ForDecoratorNodeDefinition, WhileDecoratorNodeDefinition};
+use crate::bts::decorator::reactive::ReactiveConditionDecoratorNodeDefinition;
+use crate::bts::root::{OneOffRootBTNodeDefinition, ReactiveRootBTNodeDefinition, RootBTNodeDefinition, ToFirstErrorRootBTNodeDefinition, UntilStoppedRootBTNodeDefinition};
+
+///
+/// Tree definition in the form it is accepted and stored in, the nodes are
+/// given by their type.
+///
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SerializedBehaviorTreeDefinition {
+
+    id: i32,
+
+    #[serde(default)]
+    arguments: ArgumentsExtractor,
+
+    #[serde(default)]
+    endpoints: Vec<ArgumentSetDefinition>,
+
+    nodes: Vec<NodeDefinition>,
+    root: RootNodeDefinition
+
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum NodeDefinition {
+
+    Condition(ConditionDecoratorNodeDefinition),
+    ExecuteSubTree(ExecuteSubTreeActionNodeDefinition),
+    Fallback(FallbackCompositeNodeDefinition),
+    For(ForDecoratorNodeDefinition),
+    ForEach(ForEachDecoratorNodeDefinition),
+    Invert(InvertDecoratorNodeDefinition),
+    Parallel(ParallelCompositeNodeDefinition),
+    PrintLog(PrintLogActionNodeDefinition),
+    PrioritySelector(PrioritySelectorCompositeNodeDefinition),
+    ReactiveCondition(ReactiveConditionDecoratorNodeDefinition),
+    ReactiveFallback(ReactiveFallbackCompositeNodeDefinition),
+    ReactiveSequence(ReactiveSequenceCompositeNodeDefinition),
+    Sequence(SequenceCompositeNodeDefinition),
+    UtilitySelector(UtilitySelectorCompositeNodeDefinition),
+    WaitDuration(WaitDurationActionNodeDefinition),
+    WaitUntil(WaitUntilActionNodeDefinition),
+    While(WhileDecoratorNodeDefinition)
+
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum RootNodeDefinition {
+
+    OneOff(OneOffRootBTNodeDefinition),
+    Reactive(ReactiveRootBTNodeDefinition),
+    ToFirstError(ToFirstErrorRootBTNodeDefinition),
+    UntilStopped(UntilStoppedRootBTNodeDefinition)
+
+}
+
+impl SerializedBehaviorTreeDefinition {
+
+    pub fn new(id: i32,
+               arguments: ArgumentsExtractor,
+               endpoints: Vec<ArgumentSetDefinition>,
+               nodes: Vec<NodeDefinition>,
+               root: RootNodeDefinition) -> SerializedBehaviorTreeDefinition {
+        SerializedBehaviorTreeDefinition {
+            id,
+            arguments,
+            endpoints,
+            nodes,
+            root
+        }
+    }
+
+    pub fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    pub fn get_nodes(&self) -> &Vec<NodeDefinition> {
+        &self.nodes
+    }
+
+    pub fn get_root(&self) -> &RootNodeDefinition {
+        &self.root
+    }
+
+    ///
+    /// Ids of the trees executed directly by the nodes of this one, pinned to a version or not.
+    ///
+    pub fn get_direct_subtree_ids(&self) -> HashSet<i32> {
+        self.nodes
+            .iter()
+            .filter_map(|node| node.get_subtree())
+            .map(|subtree| *subtree.get_tree_id())
+            .collect()
+    }
+
+    ///
+    /// Ids of the trees executed directly in their latest version.
+    ///
+    pub fn get_latest_subtree_ids(&self) -> HashSet<i32> {
+        self.nodes
+            .iter()
+            .filter_map(|node| node.get_subtree())
+            .filter(|subtree| subtree.get_version().is_none())
+            .map(|subtree| *subtree.get_tree_id())
+            .collect()
+    }
+
+    pub fn to_definition(&self) -> BehaviorTreeDefinition {
+        BehaviorTreeDefinition::with_arguments(
+            self.id,
+            self.arguments.clone(),
+            self.nodes
+                .iter()
+                .map(|node| Arc::new(node.clone()) as Arc<dyn BehaviorTreeNodeDefinition>)
+                .collect(),
+            self.endpoints.clone(),
+            Box::new(self.root.clone()))
+    }
+
+}
+
+impl NodeDefinition {
+
+    pub fn get_subtree(&self) -> Option<&ExecuteSubTreeActionNodeDefinition> {
+        match self {
+            NodeDefinition::ExecuteSubTree(definition) => Some(definition),
+            _ => None
+        }
+    }
+
+    fn get_definition(&self) -> &dyn BehaviorTreeNodeDefinition {
+        match self {
+            NodeDefinition::Condition(definition) => definition,
+            NodeDefinition::ExecuteSubTree(definition) => definition,
+            NodeDefinition::Fallback(definition) => definition,
+            NodeDefinition::For(definition) => definition,
+            NodeDefinition::ForEach(definition) => definition,
+            NodeDefinition::Invert(definition) => definition,
+            NodeDefinition::Parallel(definition) => definition,
+            NodeDefinition::PrintLog(definition) => definition,
+            NodeDefinition::PrioritySelector(definition) => definition,
+            NodeDefinition::ReactiveCondition(definition) => definition,
+            NodeDefinition::ReactiveFallback(definition) => definition,
+            NodeDefinition::ReactiveSequence(definition) => definition,
+            NodeDefinition::Sequence(definition) => definition,
+            NodeDefinition::UtilitySelector(definition) => definition,
+            NodeDefinition::WaitDuration(definition) => definition,
+            NodeDefinition::WaitUntil(definition) => definition,
+            NodeDefinition::While(definition) => definition
+        }
+    }
+
+}
+
+impl BehaviorTreeNodeDefinition for NodeDefinition {
+    fn build(&self,
+             context: &BehaviorTreeBuildingContext) -> Result<BTNode, BehaviorTreeBuildingError> {
+        self.get_definition().build(context)
+    }
+
+    fn get_id(&self) -> &i32 {
+        self.get_definition().get_id()
+    }
+
+    fn get_subtree_ids(&self,
+                       service: &BehaviorTreeDefinitionService)
+        -> Result<HashSet<i32>, BehaviorTreeBuildingError> {
+        self.get_definition().get_subtree_ids(service)
+    }
+
+    fn get_pinned_subtrees(&self) -> HashSet<(i32, u32)> {
+        self.get_definition().get_pinned_subtrees()
+    }
+}
+
+impl RootBTNodeDefinition for RootNodeDefinition {
+    fn build(&self,
+             context: &BehaviorTreeBuildingContext) -> Result<RootBTNode, BehaviorTreeBuildingError> {
+        match self {
+            RootNodeDefinition::OneOff(definition) => definition.build(context),
+            RootNodeDefinition::Reactive(definition) => definition.build(context),
+            RootNodeDefinition::ToFirstError(definition) => definition.build(context),
+            RootNodeDefinition::UntilStopped(definition) => definition.build(context)
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use buttercup_api::bts::{BehaviorTreeBuildingError, BehaviorTreeDefinitionService};
+use buttercup_api::bts::management::{BehaviorTreeManagementError, BehaviorTreeManagementService,
+                                      BehaviorTreeStore};
+use buttercup_api::bts::serialized::SerializedBehaviorTreeDefinition;
+use buttercup_bts::tree::BehaviorTreeService;
+
+fn build_definition(id: i32,
+                    nodes: &str,
+                    child_id: i32) -> SerializedBehaviorTreeDefinition {
+    serde_json::from_str(
+        &format!("{{\"id\": {}, \"nodes\": [{}], \"root\": {{\"OneOff\": {{\"id\": 1, \"child_id\": {}}}}}}}",
+                 id, nodes, child_id))
+        .unwrap()
+}
+
+#[test]
+fn test_manages_tree_definitions() {
+    let tree_service = Arc::new(BehaviorTreeService::default());
+    let service = BehaviorTreeManagementService::new(
+        tree_service.clone(), Arc::new(BehaviorTreeDefinitionService::default()));
+
+    let log = "{\"PrintLog\": {\"id\": 2, \"message\": \"Alive\"}}";
+    let subtree = "{\"ExecuteSubTree\": {\"id\": 2, \"tree_id\": 10}}";
+    let cycle = "{\"ExecuteSubTree\": {\"id\": 2, \"tree_id\": 11}}";
+    let pinned = "{\"ExecuteSubTree\": {\"id\": 2, \"tree_id\": 10, \"version\": 1}}";
+
+    assert_eq!(Ok(()), service.create(build_definition(10, log, 2)));
+    assert!(tree_service.get_by_id(&10).is_some());
+    assert_eq!(Err(BehaviorTreeManagementError::TreeOfGivenIdAlreadyExists(10)),
+               service.create(build_definition(10, log, 2)));
+    assert_eq!(
+        Err(BehaviorTreeManagementError::BuildingError(
+            BehaviorTreeBuildingError::CouldNotFindChildDefinitionWithId(3))),
+        service.update(&10, build_definition(10, log, 3)));
+    assert_eq!(Ok(()), service.update(&10, build_definition(10, log, 2)));
+    assert_eq!(vec![1, 2], service.get_versions(&10));
+    assert_eq!(Ok(()), service.create(build_definition(11, subtree, 2)));
+    assert_eq!(Err(BehaviorTreeManagementError::CyclicSubtreeReference(10)),
+               service.update(&10, build_definition(10, cycle, 2)));
+    assert_eq!(Err(BehaviorTreeManagementError::TreeIsUsedAsSubtree(11)),
+               service.delete(&10));
+    assert_eq!(Ok(()), service.update(&10, build_definition(10, pinned, 2)));
+    assert_eq!(
+        Err(BehaviorTreeManagementError::BuildingError(
+            BehaviorTreeBuildingError::CouldNotFindSubtreeVersion(10, 5))),
+        service.create(build_definition(12, &pinned.replace("1}", "5}"), 2)));
+    assert_eq!(vec![10, 11],
+               service.list().iter().map(|tree| *tree.get_id()).collect::<Vec<i32>>());
+    assert_eq!(Ok(()), service.delete(&11));
+
+    tree_service.pin(&10, &2);
+
+    assert_eq!(Err(BehaviorTreeManagementError::TreeVersionIsPinned(10, 2)), service.delete(&10));
+
+    tree_service.unpin(&10, &2);
+
+    assert_eq!(Ok(()), service.delete(&10));
+    assert!(tree_service.get_by_id(&10).is_none());
+    assert!(service.get(&10).is_none());
+    assert_eq!(Ok(()), service.create(build_definition(10, log, 2)));
+    assert_eq!(vec![4], service.get_versions(&10));
+}
+
+#[test]
+fn test_restores_persisted_tree_versions() {
+    let path = std::env::temp_dir().join("buttercup_test_restores_persisted_tree_versions.json");
+    let _ = std::fs::remove_file(&path);
+
+    let log = "{\"PrintLog\": {\"id\": 2, \"message\": \"Alive\"}}";
+    let subtree = "{\"ExecuteSubTree\": {\"id\": 2, \"tree_id\": 20}}";
+
+    let service = BehaviorTreeManagementService::with_store(
+        Arc::new(BehaviorTreeService::default()),
+        Arc::new(BehaviorTreeDefinitionService::default()),
+        BehaviorTreeStore::new(path.clone()));
+
+    assert_eq!(Ok(0), service.restore());
+    assert_eq!(Ok(()), service.create(build_definition(20, log, 2)));
+    assert_eq!(Ok(()), service.update(&20, build_definition(20, log, 2)));
+    assert_eq!(Ok(()), service.create(build_definition(21, subtree, 2)));
+    assert_eq!(Ok(()), service.create(build_definition(22, log, 2)));
+    assert_eq!(Ok(()), service.delete(&22));
+
+    let tree_service = Arc::new(BehaviorTreeService::default());
+    let restored = BehaviorTreeManagementService::with_store(
+        tree_service.clone(),
+        Arc::new(BehaviorTreeDefinitionService::default()),
+        BehaviorTreeStore::new(path.clone()));
+
+    assert_eq!(Ok(2), restored.restore());
+    assert_eq!(vec![1, 2], restored.get_versions(&20));
+    assert_eq!(vec![1], restored.get_versions(&21));
+    assert_eq!(vec![20, 21],
+               restored.list().iter().map(|tree| *tree.get_id()).collect::<Vec<i32>>());
+    assert!(tree_service.get_by_id(&22).is_none());
+    assert_eq!(Ok(()), restored.create(build_definition(22, log, 2)));
+    assert_eq!(vec![2], restored.get_versions(&22));
+    assert_eq!(Ok(()), restored.update(&20, build_definition(20, log, 2)));
+    assert_eq!(vec![1, 2, 3], restored.get_versions(&20));
+
+    std::fs::remove_file(&path).unwrap();
+}
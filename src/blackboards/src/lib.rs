@@ -6,7 +6,7 @@ use std::ffi::OsString;
 use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use dashmap::DashMap;
-use rocksdb::{DB, Error, Options};
+use rocksdb::{DB, Error, IteratorMode, Options, WriteBatch};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -39,16 +39,21 @@ impl LocalBlackboardService {
     }
 
     ///
-    /// Note that this may delete bb but will not cleanup afterwards, it has to do with locking
-    /// scheme of RocksDb. This will be handled in self-healing feature.
+    /// RocksDb cannot destroy a database which is still open, hence the blackboard is closed
+    /// first, the ones still holding it get an error on access.
     ///
     pub fn destroy(&self,
                    blackboard_id: &Uuid) -> Result<(), LocalBlackboardError> {
 
         let path = {
             match self.local_blackboards.remove(blackboard_id) {
-                Some((_, blackboard)) =>
-                    blackboard.as_ref().get_path(),
+                Some((_, blackboard)) => {
+                    let path = blackboard.get_path()?;
+
+                    blackboard.close()?;
+
+                    Result::Ok(path)
+                },
                 None =>
                     Result::Err(
                         LocalBlackboardError::BlackboardOfGivenIdNotFound(blackboard_id.clone()))
@@ -78,6 +83,7 @@ impl LocalBlackboardService {
 pub enum LocalBlackboardError {
 
     AccessError(String),
+    BlackboardClosed,
     BlackboardOfGivenIdNotFound(Uuid),
     DbError(String),
     DeserializeError(String),
@@ -86,14 +92,14 @@ pub enum LocalBlackboardError {
 
 }
 
-impl From<PoisonError<RwLockReadGuard<'_, DB>>> for LocalBlackboardError {
-    fn from(err: PoisonError<RwLockReadGuard<'_, DB>>) -> Self {
+impl From<PoisonError<RwLockReadGuard<'_, Option<DB>>>> for LocalBlackboardError {
+    fn from(err: PoisonError<RwLockReadGuard<'_, Option<DB>>>) -> Self {
         LocalBlackboardError::LockPoisonedError(err.to_string())
     }
 }
 
-impl From<PoisonError<RwLockWriteGuard<'_, DB>>> for LocalBlackboardError {
-    fn from(err: PoisonError<RwLockWriteGuard<'_, DB>>) -> Self {
+impl From<PoisonError<RwLockWriteGuard<'_, Option<DB>>>> for LocalBlackboardError {
+    fn from(err: PoisonError<RwLockWriteGuard<'_, Option<DB>>>) -> Self {
         LocalBlackboardError::LockPoisonedError(err.to_string())
     }
 }
@@ -106,7 +112,7 @@ impl From<rocksdb::Error> for LocalBlackboardError {
 
 pub struct LocalBlackboard {
 
-    db: Arc<RwLock<DB>>
+    db: Arc<RwLock<Option<DB>>>
 
 }
 
@@ -115,7 +121,7 @@ impl LocalBlackboard {
     pub fn new(path: OsString) -> Result<LocalBlackboard, LocalBlackboardError>  {
         Result::Ok(
             LocalBlackboard {
-                db: Arc::new(RwLock::new(DB::open_default(path)?))
+                db: Arc::new(RwLock::new(Some(DB::open_default(path)?)))
             }
         )
     }
@@ -128,31 +134,106 @@ impl LocalBlackboard {
         Result::Ok(())
     }
 
+    ///
+    /// Closes the database, every later access to the blackboard fails.
+    ///
+    pub fn close(&self) -> Result<(), LocalBlackboardError> {
+        self.db.as_ref().write()?.take();
+
+        Result::Ok(())
+    }
+
     pub fn get_path(&self) -> Result<OsString, LocalBlackboardError> {
-        Result::Ok(self.db.as_ref().read()?.path().to_path_buf().into_os_string())
+        let db = self.db.as_ref().read()?;
+
+        Result::Ok(LocalBlackboard::get_db(&db)?.path().to_path_buf().into_os_string())
     }
 
     pub fn get_value(&self,
                      value_name: &String) -> Result<Option<ValueHolder>, LocalBlackboardError> {
-        LocalBlackboard::do_get_value(&self.db.as_ref().read()?, value_name)
+        let db = self.db.as_ref().read()?;
+
+        LocalBlackboard::do_get_value(LocalBlackboard::get_db(&db)?, value_name)
     }
 
     pub fn get_values(&self,
                       value_names: &HashSet<String>) -> Result<ValuesPayload, LocalBlackboardError> {
-        LocalBlackboard::do_get_values(self.db.as_ref().read()?, value_names)
+        let db = self.db.as_ref().read()?;
+
+        LocalBlackboard::do_get_values(LocalBlackboard::get_db(&db)?, value_names)
     }
 
     pub fn put_values(&self,
                       payload: &ValuesPayload) -> Result<(), LocalBlackboardError> {
-        LocalBlackboard::do_put_values(self.db.as_ref().write()?, payload)
+        let db = self.db.as_ref().write()?;
+
+        LocalBlackboard::do_put_values(LocalBlackboard::get_db(&db)?, payload)
+    }
+
+    pub fn get_all_values(&self) -> Result<ValuesPayload, LocalBlackboardError> {
+        let db = self.db.as_ref().read()?;
+        let db = LocalBlackboard::get_db(&db)?;
+        let mut ret: HashMap<String, ValueHolder> = HashMap::new();
+
+        for (key, value) in db.iterator(IteratorMode::Start) {
+            let value_name = String::from_utf8(key.to_vec())
+                .map_err(|e| LocalBlackboardError::DeserializeError(format!("{}", e)))?;
+            let value_holder = bincode::deserialize(&value)
+                .map_err(|e| LocalBlackboardError::DeserializeError(format!("{}", e)))?;
+
+            ret.insert(value_name, value_holder);
+        }
+
+        Result::Ok(ValuesPayload::new(ret))
+    }
+
+    ///
+    /// Removes the values missing from the payload and puts the ones in it, all at once.
+    /// Returns the names of the removed values.
+    ///
+    pub fn replace_values(&self,
+                          payload: &ValuesPayload) -> Result<HashSet<String>, LocalBlackboardError> {
+        let db = self.db.as_ref().write()?;
+        let db = LocalBlackboard::get_db(&db)?;
+
+        let mut batch = WriteBatch::default();
+        let mut removed_names = HashSet::new();
+
+        for (key, _) in db.iterator(IteratorMode::Start) {
+            match std::str::from_utf8(&key) {
+                Ok(value_name) if payload.get_values().contains_key(value_name) => {},
+                Ok(value_name) => {
+                    removed_names.insert(value_name.to_owned());
+                    batch.delete(&key);
+                },
+                Err(_) => batch.delete(&key)
+            }
+        }
+
+        for (value_name, value_holder) in payload.get_values() {
+            let value = bincode::serialize(value_holder)
+                .map_err(|e| LocalBlackboardError::SerializeError(format!("{}", e)))?;
+
+            batch.put(value_name, value);
+        }
+
+        db.write(batch)
+            .map_err(|e| LocalBlackboardError::AccessError(e.into_string()))?;
+
+        Result::Ok(removed_names)
+    }
+
+    #[inline(always)]
+    fn get_db(db: &Option<DB>) -> Result<&DB, LocalBlackboardError> {
+        db.as_ref().ok_or(LocalBlackboardError::BlackboardClosed)
     }
 
     #[inline(always)]
-    fn do_get_values(db: RwLockReadGuard<DB>,
+    fn do_get_values(db: &DB,
                      value_names: &HashSet<String>) -> Result<ValuesPayload, LocalBlackboardError> {
         let mut ret: HashMap<String, ValueHolder> = HashMap::new();
         for value_name in value_names {
-            match LocalBlackboard::do_get_value(&db, value_name) {
+            match LocalBlackboard::do_get_value(db, value_name) {
                 Ok(value_holder_opt) =>
                     match value_holder_opt {
                         None => {},
@@ -167,7 +248,7 @@ impl LocalBlackboard {
     }
 
     #[inline(always)]
-    fn do_get_value(db: &RwLockReadGuard<DB>,
+    fn do_get_value(db: &DB,
                     value_name: &String) -> Result<Option<ValueHolder>, LocalBlackboardError> {
         match db.get(value_name) {
             Ok(Some(value)) =>
@@ -184,7 +265,7 @@ impl LocalBlackboard {
         }
     }
     #[inline(always)]
-    fn do_put_values(db: RwLockWriteGuard<DB>,
+    fn do_put_values(db: &DB,
                      payload: &ValuesPayload) -> Result<(), LocalBlackboardError> {
         for kv in payload.get_values().iter() {
             match bincode::serialize(kv.1) {
@@ -1,7 +1,10 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::sync::Arc;
-use log::info;
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use log::{info, warn};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -10,18 +13,30 @@ use buttercup_blackboards::{LocalBlackboard, LocalBlackboardError, LocalBlackboa
 use buttercup_values::{ValueHolder, ValuesPayload};
 use buttercup_variables::{VariableName, VariableService, VariableServiceErrorReport, VariableValueAccessError};
 
+use crate::context::clock::{Clock, SystemClock};
+use crate::context::debug::DebugContext;
+use crate::context::metrics::ExecutionMetrics;
 use crate::context::reactive::ReactiveContext;
+use crate::context::recording::RecordingContext;
+use crate::context::selection::SelectionContext;
 use crate::node::BTNode;
-use buttercup_endpoints::endpoints::EndpointService;
-use crate::events::{BTNodeExecutionEndedEvent, BTNodeExecutionStartedEvent};
-
+use crate::node::decorator::reactive::DataChangeHandlingError;
+use buttercup_endpoints::endpoints::{EndpointService, Listener};
+use crate::events::{BTNodeExecutionEndedEvent, BTNodeExecutionEvent, BTNodeExecutionStartedEvent, ReactiveErrorEvent, ValuesChangedEvent};
+use crate::events::sink::BTNodeExecutionEventSink;
+
+pub mod clock;
+pub mod debug;
+pub mod metrics;
 pub mod reactive;
+pub mod recording;
+pub mod selection;
 
 pub struct BTNodeExecutionContextHolder {
 
     id: Uuid,
     context: Arc<BTNodeExecutionContext>,
-    value_changes_listener: Arc<dyn Fn(&HashSet<String>) + Send + Sync>
+    value_changes_listener: Listener
 
 }
 
@@ -34,14 +49,12 @@ impl BTNodeExecutionContextHolder {
             Arc::new(
                 BTNodeExecutionContext::new(
                     local_blackboard,
-                    reactive_service.clone()));
+                    reactive_service));
 
         BTNodeExecutionContextHolder {
             id,
             context: context.clone(),
-            value_changes_listener: Arc::new(move |changed|
-                reactive_service.handle_value_changes(
-                    context.as_ref(), changed))
+            value_changes_listener: Arc::new(move |changed| context.handle_value_changes(changed))
         }
     }
 
@@ -53,7 +66,7 @@ impl BTNodeExecutionContextHolder {
         self.context.as_ref()
     }
 
-    pub fn get_value_changes_listener(&self) -> Arc<dyn Fn(&HashSet<String>) + Send + Sync> {
+    pub fn get_value_changes_listener(&self) -> Listener {
         self.value_changes_listener.clone()
     }
 
@@ -61,8 +74,14 @@ impl BTNodeExecutionContextHolder {
 
 pub struct BTNodeExecutionContext {
 
+    clock: Arc<dyn Clock>,
+    debug_context: DebugContext,
+    event_sink: Arc<BTNodeExecutionEventSink>,
     local_blackboard: Arc<LocalBlackboard>,
+    metrics: ExecutionMetrics,
     reactive_service: Arc<ReactiveContext>,
+    recording_context: RecordingContext,
+    selection_context: SelectionContext
 
 }
 
@@ -70,43 +89,141 @@ impl BTNodeExecutionContext {
 
     pub fn new(local_blackboard: Arc<LocalBlackboard>,
                reactive_service: Arc<ReactiveContext>) -> BTNodeExecutionContext {
+        BTNodeExecutionContext::with_clock(
+            Arc::new(SystemClock), local_blackboard, reactive_service)
+    }
+
+    pub fn with_clock(clock: Arc<dyn Clock>,
+                      local_blackboard: Arc<LocalBlackboard>,
+                      reactive_service: Arc<ReactiveContext>) -> BTNodeExecutionContext {
         BTNodeExecutionContext {
+            clock,
+            debug_context: DebugContext::default(),
+            event_sink: Arc::new(BTNodeExecutionEventSink::default()),
             local_blackboard,
-            reactive_service
+            metrics: ExecutionMetrics::default(),
+            reactive_service,
+            recording_context: RecordingContext::default(),
+            selection_context: SelectionContext::default()
         }
     }
 
     pub async fn consume_execution_started_event(&self,
                                                  event: BTNodeExecutionStartedEvent<'_>) {
-        info!("{:?}", event)
+        info!("{:?}", event);
+
+        self.event_sink.publish(&BTNodeExecutionEvent::ExecutionStarted(&event));
     }
 
     pub async fn consume_execution_ended_event(&self,
                                                event: BTNodeExecutionEndedEvent<'_>) {
-        info!("{:?}", event)
+        info!("{:?}", event);
+
+        self.event_sink.publish(&BTNodeExecutionEvent::ExecutionEnded(&event));
+    }
+
+    pub fn consume_values_changed_event(&self,
+                                        event: ValuesChangedEvent<'_>) {
+        self.event_sink.publish(&BTNodeExecutionEvent::ValuesChanged(&event));
+    }
+
+    ///
+    /// Records the changes, if recording, before notifying the reactive nodes and event listeners.
+    ///
+    pub fn handle_value_changes(&self,
+                                changed_values: &ValuesPayload) {
+        self.recording_context.observe_value_changes(changed_values);
+
+        for err in self.reactive_service.handle_value_changes(self, changed_values) {
+            self.report_reactive_error(&err);
+        }
+
+        self.consume_values_changed_event(
+            ValuesChangedEvent::new(self.clock.now(), changed_values.get_keys()));
+    }
+
+    ///
+    /// Counts the error and publishes it, it is up to the listeners to decide whether
+    /// the execution should go on.
+    ///
+    pub fn report_reactive_error(&self,
+                                 err: &DataChangeHandlingError) {
+        warn!("{:?}", err);
+
+        self.metrics.record_reactive_error(err);
+        self.event_sink.publish(
+            &BTNodeExecutionEvent::ReactiveErrorOccurred(
+                &ReactiveErrorEvent::new(self.clock.now(), err)));
+    }
+
+    ///
+    /// Reads the clock, through the recording so that replays observe the same time.
+    ///
+    pub fn now(&self) -> NaiveDateTime {
+        self.recording_context.observe_clock(self)
+    }
+
+    pub async fn sleep(&self,
+                       duration: Duration) {
+        self.clock.sleep(duration).await
+    }
+
+    pub fn get_clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    pub fn get_debug_context(&self) -> &DebugContext {
+        &self.debug_context
+    }
+
+    pub fn get_event_sink(&self) -> &Arc<BTNodeExecutionEventSink> {
+        &self.event_sink
+    }
+
+    pub fn get_metrics(&self) -> &ExecutionMetrics {
+        &self.metrics
     }
 
     pub fn get_reactive_service(&self) -> &Arc<ReactiveContext> {
         &self.reactive_service
     }
 
+    pub fn get_recording_context(&self) -> &RecordingContext {
+        &self.recording_context
+    }
+
+    pub fn get_selection_context(&self) -> &SelectionContext {
+        &self.selection_context
+    }
+
     pub fn get_values(&self,
                       value_names: &HashSet<String>) -> Result<ValuesPayload, LocalBlackboardError> {
         if value_names.is_empty() {
             return Result::Ok(ValuesPayload::empty());
         }
 
-        self.local_blackboard.get_values(value_names)
+        self.recording_context.observe_values_read(
+            value_names, self, || self.local_blackboard.get_values(value_names))
     }
 
     pub fn get_value(&self,
                      value_name: &String) -> Result<Option<ValueHolder>, LocalBlackboardError> {
-        self.local_blackboard.get_value(value_name)
+        self.recording_context.observe_value_read(
+            value_name, self, || self.local_blackboard.get_value(value_name))
     }
 
+    ///
+    /// Writes the values of the nodes, the changes are published like the ones delivered
+    /// by the endpoints.
+    ///
     pub fn put_values(&self,
                       payload: &ValuesPayload) -> Result<(), LocalBlackboardError> {
-        self.local_blackboard.put_values(payload)
+        self.local_blackboard.put_values(payload)?;
+
+        self.consume_values_changed_event(
+            ValuesChangedEvent::new(self.clock.now(), payload.get_keys()));
+
+        Result::Ok(())
     }
 
     fn map_err(err: LocalBlackboardError) -> VariableValueAccessError {
@@ -121,8 +238,7 @@ impl VariableService for BTNodeExecutionContext {
     fn get_variable_value_by_name(&self,
                                   name: &VariableName)
                                   -> Result<Option<ValueHolder>, VariableValueAccessError> {
-        self.local_blackboard
-            .get_value(name.get_value())
+        self.get_value(name.get_value())
             .map_err(BTNodeExecutionContext::map_err)
     }
 }
@@ -140,6 +256,7 @@ pub struct BTNodeContextService {
 
     contexts: DashMap<Uuid, Arc<BTNodeExecutionContextHolder>>,
     endpoint_service: Arc<EndpointService>,
+    listener_ids: DashMap<Uuid, Uuid>,
     local_blackboard_service: Arc<LocalBlackboardService>
 
 }
@@ -164,24 +281,83 @@ impl BTNodeContextService {
         BTNodeContextService {
             contexts: DashMap::new(),
             endpoint_service,
+            listener_ids: DashMap::new(),
             local_blackboard_service
         }
     }
 
     pub fn build_new(&self) -> Result<BTNodeExecutionContextHolder, BTNodeContextServiceError> {
+        self.build_with_values(&ValuesPayload::empty())
+    }
+
+    ///
+    /// Puts the values to the blackboard before the context starts listening to changes,
+    /// the blackboard is destroyed if that fails.
+    ///
+    pub fn build_with_values(&self,
+                             values: &ValuesPayload)
+                             -> Result<BTNodeExecutionContextHolder, BTNodeContextServiceError> {
         let uuid = Uuid::new_v4();
         let blackboard_service =
             self.local_blackboard_service.create(
                 &uuid, format!("{}.bb", &uuid).into())?;
 
-        let holder = BTNodeExecutionContextHolder::new(
-            uuid,
-            blackboard_service,
-            Arc::new(ReactiveContext::new()));
+        if let Err(err) = blackboard_service.put_values(values) {
+            drop(blackboard_service);
+
+            self.local_blackboard_service.destroy(&uuid)?;
 
-        self.endpoint_service.add_listener(holder.get_value_changes_listener());
+            return Result::Err(err.into());
+        }
 
-        Result::Ok(holder)
+        Result::Ok(self.listen(uuid, blackboard_service))
+    }
+
+    ///
+    /// Opens the blackboard left by the context of the given id, e.g. before the process
+    /// restarted, keeping its values. Only the given values missing from it are put.
+    ///
+    pub fn reopen_with_values(&self,
+                              id: &Uuid,
+                              values: &ValuesPayload)
+                              -> Result<BTNodeExecutionContextHolder, BTNodeContextServiceError> {
+        let blackboard_service =
+            self.local_blackboard_service.create(id, format!("{}.bb", id).into())?;
+
+        let present_values = blackboard_service.get_all_values()?;
+        let missing_values: HashMap<String, ValueHolder> = values
+            .get_values()
+            .iter()
+            .filter(|(name, _)| !present_values.get_keys().contains(*name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+
+        blackboard_service.put_values(&ValuesPayload::new(missing_values))?;
+
+        Result::Ok(self.listen(*id, blackboard_service))
+    }
+
+    ///
+    /// Stops listening to the value changes, aborts the reactive executions and destroys
+    /// the blackboard of the context.
+    ///
+    pub fn destroy(&self,
+                   holder: &BTNodeExecutionContextHolder) -> Result<(), BTNodeContextServiceError> {
+        let id = holder.get_id();
+
+        if let Some((_, listener_id)) = self.listener_ids.remove(id) {
+            self.endpoint_service.remove_listener(id, &listener_id);
+        }
+
+        self.contexts.remove(id);
+
+        holder.get_context().get_reactive_service().clear();
+
+        Result::Ok(self.local_blackboard_service.destroy(id)?)
+    }
+
+    pub fn get_endpoint_service(&self) -> &Arc<EndpointService> {
+        &self.endpoint_service
     }
 
     pub fn insert(&self,
@@ -196,14 +372,41 @@ impl BTNodeContextService {
             .map(|context_arc| context_arc.clone())
     }
 
+    fn listen(&self,
+              id: Uuid,
+              blackboard_service: Arc<LocalBlackboard>) -> BTNodeExecutionContextHolder {
+        let holder = BTNodeExecutionContextHolder::new(
+            id,
+            blackboard_service,
+            Arc::new(ReactiveContext::new()));
+
+        let listener_id =
+            self.endpoint_service.add_listener(id, holder.get_value_changes_listener());
+        self.listener_ids.insert(id, listener_id);
+
+        holder
+    }
+
 }
 
 pub mod test_utils {
     use std::ffi::OsString;
+    use std::sync::Arc;
+
+    use uuid::Uuid;
 
     use buttercup_blackboards::LocalBlackboard;
 
     use crate::context::BTNodeExecutionContext;
+    use crate::context::clock::Clock;
+
+    pub fn with_clock(clock: Arc<dyn Clock>) -> BTNodeExecutionContext {
+        BTNodeExecutionContext::with_clock(
+            clock,
+            Arc::new(
+                LocalBlackboard::new(format!("{}.bb", Uuid::new_v4()).into()).unwrap()),
+            Arc::new(Default::default()))
+    }
 
     pub fn cleanup(context: &BTNodeExecutionContext) {
         destroy(get_path(context));
@@ -218,3 +421,43 @@ pub mod test_utils {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::context::clock::ManualClock;
+    use crate::events::BTNodeExecutionEvent;
+
+    use super::*;
+
+    #[test]
+    fn test_publishes_values_written_by_nodes() {
+        let path = {
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
+            let published = Arc::new(AtomicUsize::new(0));
+            let published_ref = published.clone();
+
+            context.get_event_sink().add_listener(
+                Arc::new(move |event| {
+                    if let BTNodeExecutionEvent::ValuesChanged(_) = event {
+                        published_ref.fetch_add(1, Ordering::SeqCst);
+                    }
+                }));
+
+            let mut values = HashMap::new();
+
+            values.insert("index".to_owned(), ValueHolder::Boolean(true));
+
+            context.put_values(&ValuesPayload::new(values)).unwrap();
+
+            assert_eq!(1, published.load(Ordering::SeqCst));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+}
@@ -0,0 +1,158 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_std::task;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use chrono::naive::MAX_DATETIME;
+use futures::channel::oneshot;
+
+///
+/// Source of time for the execution, all time based nodes read and wait through it.
+///
+#[async_trait]
+pub trait Clock: Send + Sync {
+
+    fn now(&self) -> NaiveDateTime;
+
+    async fn sleep(&self, duration: Duration);
+
+}
+
+#[derive(Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+
+    fn now(&self) -> NaiveDateTime {
+        Utc::now().naive_utc()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        task::sleep(duration).await;
+    }
+
+}
+
+///
+/// Clock which only moves when advanced, waking up the sleepers whose deadline has passed.
+///
+pub struct ManualClock {
+
+    now: Mutex<NaiveDateTime>,
+    sleepers: Mutex<Vec<(NaiveDateTime, oneshot::Sender<()>)>>
+
+}
+
+impl ManualClock {
+
+    pub fn new(now: NaiveDateTime) -> ManualClock {
+        ManualClock {
+            now: Mutex::new(now),
+            sleepers: Mutex::new(Vec::new())
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let now = {
+            let mut now = self.now.lock().unwrap();
+            *now = ManualClock::add(&now, duration);
+            *now
+        };
+
+        let mut sleepers = self.sleepers.lock().unwrap();
+        let (woken, waiting): (Vec<_>, Vec<_>) = sleepers
+            .drain(..)
+            .partition(|(deadline, _)| *deadline <= now);
+
+        *sleepers = waiting;
+
+        for (_, sender) in woken {
+            let _ = sender.send(());
+        }
+    }
+
+    pub fn get_sleepers_count(&self) -> usize {
+        self.sleepers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, sender)| !sender.is_canceled())
+            .count()
+    }
+
+    fn add(now: &NaiveDateTime,
+           duration: Duration) -> NaiveDateTime {
+        chrono::Duration::from_std(duration)
+            .ok()
+            .and_then(|duration| now.checked_add_signed(duration))
+            .unwrap_or(MAX_DATETIME)
+    }
+
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new(Utc::now().naive_utc())
+    }
+}
+
+#[async_trait]
+impl Clock for ManualClock {
+
+    fn now(&self) -> NaiveDateTime {
+        *self.now.lock().unwrap()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let receiver = {
+            let now = self.now.lock().unwrap();
+
+            let deadline = ManualClock::add(&now, duration);
+
+            if deadline <= *now {
+                return;
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            let mut sleepers = self.sleepers.lock().unwrap();
+            sleepers.retain(|(_, sender)| !sender.is_canceled());
+            sleepers.push((deadline, sender));
+            receiver
+        };
+
+        let _ = receiver.await;
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_manual_clock_wakes_sleepers_once_deadline_passed() {
+        let clock = ManualClock::default();
+        let started_at = clock.now();
+
+        let mut sleep = clock.sleep(Duration::from_secs(3600)).boxed();
+
+        assert!((&mut sleep).now_or_never().is_none());
+        assert_eq!(1, clock.get_sleepers_count());
+
+        clock.advance(Duration::from_secs(1800));
+
+        assert!((&mut sleep).now_or_never().is_none());
+
+        clock.advance(Duration::from_secs(1800));
+
+        assert!(sleep.now_or_never().is_some());
+        assert_eq!(0, clock.get_sleepers_count());
+        assert_eq!(chrono::Duration::hours(1),
+                   clock.now().signed_duration_since(started_at));
+    }
+
+}
@@ -0,0 +1,354 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::NaiveDateTime;
+use dashmap::{DashMap, DashSet};
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::context::clock::Clock;
+use crate::tick::TickHeader;
+
+///
+/// Pauses the execution before ticking chosen nodes. Nodes are paused in
+/// `BehaviorTreeNode::tick`, hence node implementations are not aware of debugging.
+/// Pausing a node and disabling the debugger are serialized, so that no node is left paused.
+///
+#[derive(Default)]
+pub struct DebugContext {
+
+    breakpoints: DashSet<i32>,
+    enabled: AtomicBool,
+    lock: Mutex<()>,
+    paused_nodes: DashMap<Uuid, (PausedNode, oneshot::Sender<DebugCommand>)>,
+    stepping: AtomicBool
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum DebugCommand {
+
+    Continue,
+    Step
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum DebugContextError {
+
+    DebuggingNotEnabled,
+    NoPausedNodes
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub struct PausedNode {
+
+    correlation_id: Uuid,
+    node_id: i32,
+    node_tick_id: Uuid,
+    paused_at: NaiveDateTime,
+    root_tick_id: Uuid,
+    tree_id: i32,
+    tree_tick_id: Uuid
+
+}
+
+impl PausedNode {
+
+    pub fn get_node_id(&self) -> &i32 {
+        &self.node_id
+    }
+
+    pub fn get_node_tick_id(&self) -> &Uuid {
+        &self.node_tick_id
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DebugState {
+
+    breakpoints: Vec<i32>,
+    enabled: bool,
+    paused_nodes: Vec<PausedNode>,
+    stepping: bool
+
+}
+
+impl DebugState {
+
+    pub fn get_paused_nodes(&self) -> &Vec<PausedNode> {
+        &self.paused_nodes
+    }
+
+}
+
+///
+/// Forgets the paused node once its tick goes on or is dropped while paused.
+///
+struct PausedNodeGuard<'a> {
+
+    node_tick_id: Uuid,
+    paused_nodes: &'a DashMap<Uuid, (PausedNode, oneshot::Sender<DebugCommand>)>
+
+}
+
+impl Drop for PausedNodeGuard<'_> {
+    fn drop(&mut self) {
+        self.paused_nodes.remove(&self.node_tick_id);
+    }
+}
+
+impl DebugContext {
+
+    pub fn enable(&self,
+                  breakpoints: HashSet<i32>) {
+        self.breakpoints.clear();
+
+        for breakpoint in breakpoints {
+            self.breakpoints.insert(breakpoint);
+        }
+
+        self.enabled.store(true, Ordering::SeqCst);
+    }
+
+    ///
+    /// Disabling the debugger lets all of the paused nodes continue.
+    ///
+    pub fn disable(&self) {
+        let _lock = self.lock.lock().unwrap();
+
+        self.enabled.store(false, Ordering::SeqCst);
+        self.stepping.store(false, Ordering::SeqCst);
+        self.breakpoints.clear();
+
+        self.send_to_paused(DebugCommand::Continue);
+    }
+
+    pub fn add_breakpoint(&self,
+                          node_id: i32) -> Result<(), DebugContextError> {
+        self.check_enabled()?;
+
+        self.breakpoints.insert(node_id);
+
+        Result::Ok(())
+    }
+
+    pub fn remove_breakpoint(&self,
+                             node_id: &i32) -> Result<(), DebugContextError> {
+        self.check_enabled()?;
+
+        self.breakpoints.remove(node_id);
+
+        Result::Ok(())
+    }
+
+    ///
+    /// Lets the paused nodes run and pauses again before the next node tick.
+    ///
+    pub fn step(&self) -> Result<(), DebugContextError> {
+        self.check_paused()?;
+
+        self.stepping.store(true, Ordering::SeqCst);
+        self.send_to_paused(DebugCommand::Step);
+
+        Result::Ok(())
+    }
+
+    ///
+    /// Lets the paused nodes run until the next breakpoint is hit.
+    ///
+    pub fn resume(&self) -> Result<(), DebugContextError> {
+        self.check_paused()?;
+
+        self.stepping.store(false, Ordering::SeqCst);
+        self.send_to_paused(DebugCommand::Continue);
+
+        Result::Ok(())
+    }
+
+    pub fn get_state(&self) -> DebugState {
+        let mut breakpoints: Vec<i32> =
+            self.breakpoints.iter().map(|node_id| *node_id).collect();
+        breakpoints.sort();
+
+        DebugState {
+            breakpoints,
+            enabled: self.enabled.load(Ordering::SeqCst),
+            paused_nodes: self.paused_nodes
+                .iter()
+                .map(|entry| entry.value().0.clone())
+                .collect(),
+            stepping: self.stepping.load(Ordering::SeqCst)
+        }
+    }
+
+    pub async fn before_tick(&self,
+                             node_id: &i32,
+                             node_tick_id: &Uuid,
+                             header: &TickHeader,
+                             clock: &dyn Clock) {
+        let (receiver, _guard) = {
+            let _lock = self.lock.lock().unwrap();
+
+            if !self.should_pause(node_id) {
+                return;
+            }
+
+            let (sender, receiver) = oneshot::channel();
+
+            self.paused_nodes.insert(
+                *node_tick_id,
+                (PausedNode {
+                    correlation_id: *header.get_correlation_id(),
+                    node_id: *node_id,
+                    node_tick_id: *node_tick_id,
+                    paused_at: clock.now(),
+                    root_tick_id: *header.get_root_tick_id(),
+                    tree_id: *header.get_tree_id(),
+                    tree_tick_id: *header.get_tree_tick_id()
+                }, sender));
+
+            let guard = PausedNodeGuard {
+                node_tick_id: *node_tick_id,
+                paused_nodes: &self.paused_nodes
+            };
+
+            (receiver, guard)
+        };
+
+        let _ = receiver.await;
+    }
+
+    fn should_pause(&self,
+                    node_id: &i32) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+            && (self.stepping.load(Ordering::SeqCst) || self.breakpoints.contains(node_id))
+    }
+
+    fn send_to_paused(&self,
+                      command: DebugCommand) {
+        let node_tick_ids: Vec<Uuid> =
+            self.paused_nodes.iter().map(|entry| *entry.key()).collect();
+
+        for node_tick_id in node_tick_ids {
+            if let Some((_, (_, sender))) = self.paused_nodes.remove(&node_tick_id) {
+                let _ = sender.send(command.clone());
+            }
+        }
+    }
+
+    fn check_enabled(&self) -> Result<(), DebugContextError> {
+        if !self.enabled.load(Ordering::SeqCst) {
+            return Result::Err(DebugContextError::DebuggingNotEnabled);
+        }
+
+        Result::Ok(())
+    }
+
+    fn check_paused(&self) -> Result<(), DebugContextError> {
+        self.check_enabled()?;
+
+        if self.paused_nodes.is_empty() {
+            return Result::Err(DebugContextError::NoPausedNodes);
+        }
+
+        Result::Ok(())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+    use std::time::Duration;
+
+    use async_std::task;
+    use futures::FutureExt;
+
+    use crate::context::{BTNodeExecutionContext, test_utils};
+    use crate::node::{BehaviorTreeNode, BTNode};
+    use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::composite::sequence::SequenceCompositeNode;
+    use crate::tick::TickStatus;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_pauses_at_breakpoint_and_steps_to_next_node() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let node: BTNode = SequenceCompositeNode::new(
+                1,
+                vec![
+                    PrintLogActionNode::new(2, "first".to_owned()).into(),
+                    PrintLogActionNode::new(3, "second".to_owned()).into()
+                ]).into();
+
+            context.get_debug_context().enable(HashSet::from_iter(vec![2]));
+
+            let header = TickHeader::default();
+            let (result, visited) = futures::join!(
+                node.tick(&header, &context),
+                drive_debugger(context.get_debug_context()));
+
+            assert_eq!(Result::Ok(TickStatus::Success), result);
+            assert_eq!(vec![2, 3], visited);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_forgets_paused_node_when_tick_is_dropped() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let node: BTNode = PrintLogActionNode::new(1, "paused".to_owned()).into();
+
+            context.get_debug_context().enable(HashSet::from_iter(vec![1]));
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, context.get_debug_context().get_state().get_paused_nodes().len());
+            }
+
+            assert!(context.get_debug_context().get_state().get_paused_nodes().is_empty());
+            assert_eq!(Result::Err(DebugContextError::NoPausedNodes),
+                       context.get_debug_context().resume());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    async fn drive_debugger(debug_context: &DebugContext) -> Vec<i32> {
+        let mut visited = Vec::new();
+
+        visited.push(wait_for_paused_node(debug_context).await);
+        debug_context.step().unwrap();
+
+        visited.push(wait_for_paused_node(debug_context).await);
+        debug_context.disable();
+
+        visited
+    }
+
+    async fn wait_for_paused_node(debug_context: &DebugContext) -> i32 {
+        loop {
+            if let Some(paused) = debug_context.get_state().get_paused_nodes().first() {
+                return *paused.get_node_id();
+            }
+            task::sleep(Duration::from_millis(1)).await;
+        }
+    }
+
+}
@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dashmap::DashMap;
+
+use crate::node::decorator::reactive::DataChangeHandlingError;
+
+///
+/// Counters of a single context, read by the agent owning it.
+///
+#[derive(Default)]
+pub struct ExecutionMetrics {
+
+    reactive_errors_count: AtomicUsize,
+    reactive_errors_counts_by_node_ids: DashMap<i32, usize>
+
+}
+
+impl ExecutionMetrics {
+
+    pub fn record_reactive_error(&self,
+                                 err: &DataChangeHandlingError) {
+        self.reactive_errors_count.fetch_add(1, Ordering::SeqCst);
+
+        if let Some(node_id) = err.get_node_id() {
+            *self.reactive_errors_counts_by_node_ids.entry(*node_id).or_insert(0) += 1;
+        }
+    }
+
+    pub fn get_reactive_errors_count(&self) -> usize {
+        self.reactive_errors_count.load(Ordering::SeqCst)
+    }
+
+    pub fn get_reactive_errors_count_by_node_id(&self,
+                                                node_id: &i32) -> usize {
+        self.reactive_errors_counts_by_node_ids
+            .get(node_id)
+            .map_or(0, |entry| *entry.value())
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use buttercup_blackboards::LocalBlackboardError;
+
+    use super::*;
+
+    #[test]
+    fn test_counts_reactive_errors_per_node() {
+        let metrics = ExecutionMetrics::default();
+
+        metrics.record_reactive_error(
+            &DataChangeHandlingError::BlackboardError(
+                1, LocalBlackboardError::DbError("Broken.".to_owned())));
+        metrics.record_reactive_error(
+            &DataChangeHandlingError::BlackboardError(
+                1, LocalBlackboardError::DbError("Broken.".to_owned())));
+        metrics.record_reactive_error(&DataChangeHandlingError::NonReactiveNodeCalledError);
+
+        assert_eq!(3, metrics.get_reactive_errors_count());
+        assert_eq!(2, metrics.get_reactive_errors_count_by_node_id(&1));
+        assert_eq!(0, metrics.get_reactive_errors_count_by_node_id(&2));
+    }
+
+}
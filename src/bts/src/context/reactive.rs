@@ -1,108 +1,350 @@
 use std::collections::HashSet;
-use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 
 use dashmap::{DashMap, DashSet};
-use dashmap::mapref::one::Ref;
+use futures::channel::oneshot;
 use futures::future::AbortHandle;
+use futures::FutureExt;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use buttercup_values::ValuesPayload;
 
 use crate::context::BTNodeExecutionContext;
-use crate::node::BTNode;
-use crate::node::decorator::reactive::{ReactiveConditionDecoratorNode, ReactiveConditionInnerNode};
+use crate::node::decorator::reactive::{DataChangeHandlingError, ReactiveConditionInnerNode};
 use std::ops::Deref;
 
 #[derive(Default)]
 pub struct ReactiveContext {
 
-    abort_handles: DashMap<i32, AbortHandle>,
-    nodes_by_value_names: DashMap<String, DashSet<Arc<ReactiveConditionInnerNode>>>
+    abort_handles: DashMap<ReactiveActivationKey, AbortHandle>,
+    nodes_by_value_names: DashMap<String, DashSet<ReactiveSubscriber>>
+
+}
+
+///
+/// Entry of the value names index, notified whenever one of its values changes.
+///
+#[derive(Clone, Hash, Eq, PartialEq)]
+pub enum ReactiveSubscriber {
+
+    Condition(Arc<ReactiveActivation>),
+    Watcher(Arc<ValueChangesWatcher>)
+
+}
+
+///
+/// Identifies a single activation of a reactive node. The same node may be active more than
+/// once within one tick, e.g. in a subtree shared by parallel branches, hence each activation
+/// gets an id of its own. The node id is kept for error reporting.
+///
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub struct ReactiveActivationKey {
+
+    activation_id: Uuid,
+    node_id: i32
+
+}
+
+impl ReactiveActivationKey {
+
+    pub fn new(node_id: i32) -> ReactiveActivationKey {
+        ReactiveActivationKey {
+            activation_id: Uuid::new_v4(),
+            node_id
+        }
+    }
+
+    pub fn get_node_id(&self) -> &i32 {
+        &self.node_id
+    }
+
+}
+
+pub struct ReactiveActivation {
+
+    key: ReactiveActivationKey,
+    node: Arc<ReactiveConditionInnerNode>
+
+}
+
+impl ReactiveActivation {
+
+    pub fn get_key(&self) -> &ReactiveActivationKey {
+        &self.key
+    }
+
+}
+
+impl Hash for ReactiveActivation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl PartialEq for ReactiveActivation {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for ReactiveActivation {}
+
+///
+/// Keeps the activation registered until dropped, i.e. until the guarded execution finishes
+/// or is dropped itself.
+///
+pub struct ReactiveRegistration<'a> {
+
+    activation: Arc<ReactiveActivation>,
+    reactive_context: &'a ReactiveContext
+
+}
+
+impl Drop for ReactiveRegistration<'_> {
+    fn drop(&mut self) {
+        self.reactive_context.deregister(&self.activation);
+    }
+}
+
+impl ReactiveSubscriber {
+
+    fn get_value_names(&self) -> &HashSet<String> {
+        match self {
+            ReactiveSubscriber::Condition(activation) => activation.node.get_value_names(),
+            ReactiveSubscriber::Watcher(watcher) => &watcher.value_names
+        }
+    }
+
+}
+
+pub struct ValueChangesWatcher {
+
+    id: Uuid,
+    sender: Mutex<Option<oneshot::Sender<()>>>,
+    value_names: HashSet<String>
+
+}
+
+impl ValueChangesWatcher {
+
+    fn notify(&self) {
+        if let Ok(mut sender) = self.sender.lock() {
+            if let Some(sender) = sender.take() {
+                let _ = sender.send(());
+            }
+        }
+    }
+
+}
+
+impl Hash for ValueChangesWatcher {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialEq for ValueChangesWatcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
 
+impl Eq for ValueChangesWatcher {}
+
+///
+/// Resolves once any of the watched values changes, leaving the index when dropped.
+///
+pub struct ValueChangesWatch<'a> {
+
+    reactive_context: &'a ReactiveContext,
+    receiver: oneshot::Receiver<()>,
+    watcher: Arc<ValueChangesWatcher>
+
+}
+
+impl Future for ValueChangesWatch<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver.poll_unpin(cx).map(|_| ())
+    }
+}
+
+impl Drop for ValueChangesWatch<'_> {
+    fn drop(&mut self) {
+        self.reactive_context.remove(&ReactiveSubscriber::Watcher(self.watcher.clone()));
+    }
 }
 
 #[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
 pub enum ReactiveContextError {
 
-    AbortEntryNotFound(i32)
+    AbortEntryNotFound(ReactiveActivationKey)
 
 }
 
 impl ReactiveContext {
 
     pub fn new() -> ReactiveContext {
-        ReactiveContext { abort_handles: DashMap::new(), nodes_by_value_names: DashMap::new() }
+        ReactiveContext {
+            abort_handles: DashMap::new(),
+            nodes_by_value_names: DashMap::new()
+        }
     }
 
     pub fn abort(&self,
-                 bt_node_id: &i32) -> Result<(), ReactiveContextError> {
-        match self.abort_handles.get(bt_node_id) {
-            None => Result::Err(ReactiveContextError::AbortEntryNotFound(*bt_node_id)),
+                 key: &ReactiveActivationKey) -> Result<(), ReactiveContextError> {
+        match self.abort_handles.get(key) {
+            None => Result::Err(ReactiveContextError::AbortEntryNotFound(key.clone())),
             Some(entry) => Result::Ok(entry.value().abort())
         }
     }
 
     pub fn deregister(&self,
-                      bt_node: &ReactiveConditionInnerNode) {
-        let bt_node_id = bt_node.get_id();
+                      activation: &Arc<ReactiveActivation>) {
+        self.abort_handles.remove(&activation.key);
 
-        self.abort_handles.remove(bt_node.get_id());
-
-        for value_name in bt_node.get_value_names() {
-            self.nodes_by_value_names
-                .alter(
-                    value_name,
-                    |k,v|
-                        v
-                            .into_iter()
-                            .filter(
-                                |node|
-                                    node.get_id() != bt_node_id)
-                            .collect()
-                );
-            self.nodes_by_value_names
-                .remove_if(
-                    value_name,
-                    |k, v| v.is_empty());
-        }
+        self.remove(&ReactiveSubscriber::Condition(activation.clone()));
     }
 
+    ///
+    /// Returns the errors of the conditions which could not handle the changes.
+    ///
     pub fn handle_value_changes(&self,
                                 context: &BTNodeExecutionContext,
-                                changed_value_names: &HashSet<String>) {
+                                changed_values: &ValuesPayload) -> Vec<DataChangeHandlingError> {
+        let mut errors = Vec::new();
         let mut already_called = HashSet::new();
+        let mut already_notified = HashSet::new();
+        let mut notified_watchers = Vec::new();
 
-        for value_name in changed_value_names {
-            if let Some(nodes) =
+        for value_name in changed_values.get_keys() {
+            if let Some(subscribers) =
             self.nodes_by_value_names.get(value_name) {
-                for node_entry in nodes.value().iter() {
-                    let node = node_entry.deref();
-
-                    let node_id = node.get_id();
-
-                    if !already_called.contains(node_id) {
-                        node.handle_value_change(context);
-                        already_called.insert(node_id.clone());
+                for subscriber in subscribers.value().iter() {
+                    match subscriber.deref() {
+                        ReactiveSubscriber::Condition(activation) => {
+                            if already_called.insert(activation.key.clone()) {
+                                if let Err(err) = activation.node.handle_value_change(
+                                    context, &activation.key, changed_values) {
+                                    errors.push(err);
+                                }
+                            }
+                        },
+                        ReactiveSubscriber::Watcher(watcher) => {
+                            if already_notified.insert(watcher.id) {
+                                watcher.notify();
+                                notified_watchers.push(subscriber.deref().clone());
+                            }
+                        }
                     }
                 }
             }
         }
+
+        for watcher in notified_watchers {
+            self.remove(&watcher);
+        }
+
+        errors
+    }
+
+    ///
+    /// Waits for any of the given values to change, instead of polling the blackboard.
+    /// The watch should be created before reading the values, so that no change is missed.
+    ///
+    pub fn watch(&self,
+                 value_names: HashSet<String>) -> ValueChangesWatch<'_> {
+        let (sender, receiver) = oneshot::channel();
+        let watcher = Arc::new(ValueChangesWatcher {
+            id: Uuid::new_v4(),
+            sender: Mutex::new(Some(sender)),
+            value_names
+        });
+
+        self.insert(ReactiveSubscriber::Watcher(watcher.clone()));
+
+        ValueChangesWatch {
+            reactive_context: self,
+            receiver,
+            watcher
+        }
+    }
+
+    pub fn get_watchers_count(&self) -> usize {
+        let watchers: HashSet<Uuid> = self.nodes_by_value_names
+            .iter()
+            .flat_map(|entry|
+                entry.value()
+                    .iter()
+                    .filter_map(|subscriber| match subscriber.deref() {
+                        ReactiveSubscriber::Watcher(watcher) => Some(watcher.id),
+                        _ => None
+                    })
+                    .collect::<Vec<Uuid>>())
+            .collect();
+
+        watchers.len()
     }
 
     pub fn register(&self,
                     abort_handle: AbortHandle,
-                    node: &Arc<ReactiveConditionInnerNode>) -> Result<(), ReactiveContextError> {
-        let bt_node_id =*node.get_id();
+                    key: ReactiveActivationKey,
+                    node: &Arc<ReactiveConditionInnerNode>) -> ReactiveRegistration<'_> {
+        let activation = Arc::new(ReactiveActivation { key, node: node.clone() });
+
+        self.abort_handles.insert(activation.key.clone(), abort_handle);
 
-        self.abort_handles.insert(bt_node_id, abort_handle);
+        self.insert(ReactiveSubscriber::Condition(activation.clone()));
+
+        ReactiveRegistration {
+            activation,
+            reactive_context: self
+        }
+    }
 
-        for value_name in node.get_value_names().clone() {
+    pub fn get_activations_count(&self) -> usize {
+        self.abort_handles.len()
+    }
+
+    ///
+    /// Aborts all registered executions and forgets all subscribers.
+    ///
+    pub fn clear(&self) {
+        for entry in self.abort_handles.iter() {
+            entry.value().abort();
+        }
+
+        self.abort_handles.clear();
+        self.nodes_by_value_names.clear();
+    }
+
+    fn insert(&self,
+              subscriber: ReactiveSubscriber) {
+        for value_name in subscriber.get_value_names().clone() {
             self.nodes_by_value_names
                 .entry(value_name)
                 .or_insert(DashSet::new())
                 .value()
-                .insert(node.clone());
+                .insert(subscriber.clone());
         }
+    }
 
-        Result::Ok(())
+    fn remove(&self,
+              subscriber: &ReactiveSubscriber) {
+        for value_name in subscriber.get_value_names() {
+            if let Some(subscribers) = self.nodes_by_value_names.get(value_name) {
+                subscribers.value().remove(subscriber);
+            }
+            self.nodes_by_value_names
+                .remove_if(
+                    value_name,
+                    |k, v| v.is_empty());
+        }
     }
 }
-
@@ -0,0 +1,803 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+
+use async_std::task;
+use chrono::NaiveDateTime;
+use dashmap::DashSet;
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use buttercup_blackboards::LocalBlackboardError;
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+use crate::context::BTNodeExecutionContext;
+use crate::context::reactive::ReactiveActivationKey;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+///
+/// Records everything the execution observes, so that it can be replayed offline against the same
+/// tree. While replaying, blackboard reads, clock reads and action outcomes are served from the
+/// recording and value changes are re-delivered in the recorded order.
+///
+#[derive(Default)]
+pub struct RecordingContext {
+
+    mode: RwLock<RecordingMode>,
+    preempting: DashSet<ReactiveActivationKey>
+
+}
+
+#[derive(Clone, Default)]
+enum RecordingMode {
+
+    #[default]
+    Disabled,
+    Recording(Arc<ExecutionRecorder>),
+    Replaying(Arc<ExecutionReplay>)
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum RecordingContextError {
+
+    LockPoisonedError,
+    RecordingNotStarted,
+    ReplayInProgress
+
+}
+
+///
+/// Preempted actions were cancelled by the tree itself, e.g. by a reactive or a parallel node,
+/// and are cancelled the same way when replayed. Interrupted ones were cancelled from outside
+/// of the tree, e.g. by stopping the agent, which does not happen again when replaying.
+///
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum AbortCause {
+
+    Interrupted,
+    Preempted
+
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub enum RecordedObservation {
+
+    ActionAborted(Uuid, i32, AbortCause),
+    ActionEnded(Uuid, i32, Result<TickStatus, TickError>),
+    ClockRead(NaiveDateTime),
+    ValueChanges(ValuesPayload),
+    ValueRead(String, Result<Option<ValueHolder>, LocalBlackboardError>),
+    ValuesRead(Vec<String>, Result<ValuesPayload, LocalBlackboardError>)
+
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ExecutionRecording {
+
+    observations: Vec<RecordedObservation>
+
+}
+
+impl ExecutionRecording {
+
+    pub fn new(observations: Vec<RecordedObservation>) -> ExecutionRecording {
+        ExecutionRecording {
+            observations
+        }
+    }
+
+    pub fn get_observations(&self) -> &Vec<RecordedObservation> {
+        &self.observations
+    }
+
+    ///
+    /// Correlation ids of the executions which produced the recorded action outcomes.
+    ///
+    pub fn get_correlation_ids(&self) -> HashSet<Uuid> {
+        self.observations
+            .iter()
+            .filter_map(|observation| match observation {
+                RecordedObservation::ActionAborted(correlation_id, _, _) |
+                RecordedObservation::ActionEnded(correlation_id, _, _) => Some(*correlation_id),
+                _ => None
+            })
+            .collect()
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum ReplayDivergence {
+
+    MissingActionOutcome(i32),
+    MissingClockRead,
+    MissingValueRead(String),
+    MissingValuesRead(Vec<String>),
+    UnreplayedPreemption(i32)
+
+}
+
+impl RecordingContext {
+
+    pub fn start_recording(&self) -> Result<(), RecordingContextError> {
+        let mut mode = self.mode.write()?;
+
+        if let RecordingMode::Replaying(_) = *mode {
+            return Result::Err(RecordingContextError::ReplayInProgress);
+        }
+
+        *mode = RecordingMode::Recording(Arc::new(ExecutionRecorder::default()));
+
+        Result::Ok(())
+    }
+
+    pub fn stop_recording(&self) -> Result<ExecutionRecording, RecordingContextError> {
+        let mut mode = self.mode.write()?;
+
+        let recording = match &*mode {
+            RecordingMode::Recording(recorder) => recorder.get_recording(),
+            _ => return Result::Err(RecordingContextError::RecordingNotStarted)
+        };
+
+        *mode = RecordingMode::Disabled;
+
+        Result::Ok(recording)
+    }
+
+    pub fn get_recording(&self) -> Result<ExecutionRecording, RecordingContextError> {
+        match self.get_mode() {
+            RecordingMode::Recording(recorder) => Result::Ok(recorder.get_recording()),
+            _ => Result::Err(RecordingContextError::RecordingNotStarted)
+        }
+    }
+
+    pub fn start_replay(&self,
+                        recording: &ExecutionRecording) -> Result<(), RecordingContextError> {
+        *self.mode.write()? = RecordingMode::Replaying(Arc::new(ExecutionReplay::new(recording)));
+
+        Result::Ok(())
+    }
+
+    pub fn get_replay_divergences(&self) -> Vec<ReplayDivergence> {
+        match self.get_mode() {
+            RecordingMode::Replaying(replay) => replay.get_divergences(),
+            _ => Vec::new()
+        }
+    }
+
+    pub async fn observe_action<F>(&self,
+                                   node_id: &i32,
+                                   header: &TickHeader,
+                                   context: &BTNodeExecutionContext,
+                                   action: F) -> Result<TickStatus, TickError>
+        where F: Future<Output=Result<TickStatus, TickError>> + Send {
+        match self.get_mode() {
+            RecordingMode::Disabled => action.await,
+            RecordingMode::Recording(recorder) => {
+                let mut guard = ActionOutcomeGuard::new(
+                    &recorder, &self.preempting, header, node_id);
+
+                let result = action.await;
+
+                guard.finish(&result);
+
+                result
+            },
+            RecordingMode::Replaying(replay) =>
+                match replay.next_action_outcome(node_id, context) {
+                    Some(ReplayedActionOutcome::Ended(result)) => result,
+                    Some(ReplayedActionOutcome::Aborted(AbortCause::Preempted, seq)) => {
+                        replay.await_preemption(seq).await;
+
+                        let divergence = ReplayDivergence::UnreplayedPreemption(*node_id);
+                        replay.diverge(divergence.clone());
+
+                        Result::Err(TickError::ReplayDiverged(*node_id, divergence))
+                    },
+                    Some(ReplayedActionOutcome::Aborted(AbortCause::Interrupted, _)) =>
+                        Result::Err(TickError::AbortedExecution(*node_id)),
+                    None => action.await
+                }
+        }
+    }
+
+    ///
+    /// Drops the executions cancelled by the tree itself, the actions of the given activation
+    /// aborted meanwhile are recorded as preempted.
+    ///
+    pub fn preempt<T>(&self,
+                      activation_key: &ReactiveActivationKey,
+                      preempted: T) {
+        self.preempting.insert(activation_key.clone());
+
+        drop(preempted);
+
+        self.preempting.remove(activation_key);
+    }
+
+    pub fn observe_clock(&self,
+                         context: &BTNodeExecutionContext) -> NaiveDateTime {
+        match self.get_mode() {
+            RecordingMode::Disabled => context.get_clock().now(),
+            RecordingMode::Recording(recorder) => {
+                let now = context.get_clock().now();
+                recorder.record(RecordedObservation::ClockRead(now));
+                now
+            },
+            RecordingMode::Replaying(replay) =>
+                replay
+                    .next_clock_read(context)
+                    .unwrap_or_else(|| context.get_clock().now())
+        }
+    }
+
+    pub fn observe_value_read<F>(&self,
+                                 value_name: &String,
+                                 context: &BTNodeExecutionContext,
+                                 read: F) -> Result<Option<ValueHolder>, LocalBlackboardError>
+        where F: FnOnce() -> Result<Option<ValueHolder>, LocalBlackboardError> {
+        match self.get_mode() {
+            RecordingMode::Disabled => read(),
+            RecordingMode::Recording(recorder) => {
+                let result = read();
+                recorder.record(
+                    RecordedObservation::ValueRead(value_name.clone(), result.clone()));
+                result
+            },
+            RecordingMode::Replaying(replay) =>
+                replay
+                    .next_value_read(value_name, context)
+                    .unwrap_or_else(read)
+        }
+    }
+
+    pub fn observe_values_read<F>(&self,
+                                  value_names: &HashSet<String>,
+                                  context: &BTNodeExecutionContext,
+                                  read: F) -> Result<ValuesPayload, LocalBlackboardError>
+        where F: FnOnce() -> Result<ValuesPayload, LocalBlackboardError> {
+        match self.get_mode() {
+            RecordingMode::Disabled => read(),
+            RecordingMode::Recording(recorder) => {
+                let result = read();
+                recorder.record(
+                    RecordedObservation::ValuesRead(to_key(value_names), result.clone()));
+                result
+            },
+            RecordingMode::Replaying(replay) =>
+                replay
+                    .next_values_read(&to_key(value_names), context)
+                    .unwrap_or_else(read)
+        }
+    }
+
+    pub fn observe_value_changes(&self,
+                                 changed_values: &ValuesPayload) {
+        if let RecordingMode::Recording(recorder) = self.get_mode() {
+            recorder.record(RecordedObservation::ValueChanges(changed_values.clone()));
+        }
+    }
+
+    fn get_mode(&self) -> RecordingMode {
+        match self.mode.read() {
+            Ok(mode) => mode.clone(),
+            Err(_) => RecordingMode::Disabled
+        }
+    }
+
+}
+
+impl<T> From<std::sync::PoisonError<T>> for RecordingContextError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        RecordingContextError::LockPoisonedError
+    }
+}
+
+#[derive(Default)]
+struct ExecutionRecorder {
+
+    observations: Mutex<Vec<RecordedObservation>>
+
+}
+
+impl ExecutionRecorder {
+
+    fn record(&self,
+              observation: RecordedObservation) {
+        if let Ok(mut observations) = self.observations.lock() {
+            observations.push(observation);
+        }
+    }
+
+    fn get_recording(&self) -> ExecutionRecording {
+        match self.observations.lock() {
+            Ok(observations) => ExecutionRecording::new(observations.clone()),
+            Err(_) => ExecutionRecording::default()
+        }
+    }
+
+}
+
+///
+/// Records the action as aborted unless it finished, i.e. when the action future is dropped
+/// before completion.
+///
+struct ActionOutcomeGuard<'a> {
+
+    activation_keys: Vec<ReactiveActivationKey>,
+    correlation_id: Uuid,
+    finished: bool,
+    node_id: i32,
+    preempting: &'a DashSet<ReactiveActivationKey>,
+    recorder: &'a ExecutionRecorder
+
+}
+
+impl<'a> ActionOutcomeGuard<'a> {
+
+    fn new(recorder: &'a ExecutionRecorder,
+           preempting: &'a DashSet<ReactiveActivationKey>,
+           header: &TickHeader,
+           node_id: &i32) -> ActionOutcomeGuard<'a> {
+        ActionOutcomeGuard {
+            activation_keys: header.get_activation_keys().clone(),
+            correlation_id: *header.get_correlation_id(),
+            finished: false,
+            node_id: *node_id,
+            preempting,
+            recorder
+        }
+    }
+
+    fn finish(&mut self,
+              result: &Result<TickStatus, TickError>) {
+        self.finished = true;
+        self.recorder.record(
+            RecordedObservation::ActionEnded(self.correlation_id, self.node_id, result.clone()));
+    }
+
+}
+
+impl Drop for ActionOutcomeGuard<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let cause = if self.activation_keys
+                .iter()
+                .any(|activation_key| self.preempting.contains(activation_key)) {
+                AbortCause::Preempted
+            } else {
+                AbortCause::Interrupted
+            };
+
+            self.recorder.record(
+                RecordedObservation::ActionAborted(self.correlation_id, self.node_id, cause));
+        }
+    }
+}
+
+type Sequenced<T> = VecDeque<(usize, T)>;
+
+///
+/// Aborted outcomes keep the position of the abort within the recording.
+///
+enum ReplayedActionOutcome {
+
+    Aborted(AbortCause, usize),
+    Ended(Result<TickStatus, TickError>)
+
+}
+
+#[derive(Default)]
+struct ReplayState {
+
+    action_outcomes: HashMap<i32, Sequenced<ReplayedActionOutcome>>,
+    clock_reads: Sequenced<NaiveDateTime>,
+    divergences: Vec<ReplayDivergence>,
+    preemption_waiters: Vec<(usize, oneshot::Sender<()>)>,
+    unreplayed: BTreeSet<usize>,
+    value_changes: Sequenced<ValuesPayload>,
+    value_reads: HashMap<String, Sequenced<Result<Option<ValueHolder>, LocalBlackboardError>>>,
+    values_reads: HashMap<Vec<String>, Sequenced<Result<ValuesPayload, LocalBlackboardError>>>
+
+}
+
+struct ExecutionReplay {
+
+    state: Mutex<ReplayState>
+
+}
+
+impl ExecutionReplay {
+
+    fn new(recording: &ExecutionRecording) -> ExecutionReplay {
+        let mut state = ReplayState::default();
+
+        for (seq, observation) in recording.get_observations().iter().cloned().enumerate() {
+            state.unreplayed.insert(seq);
+
+            match observation {
+                RecordedObservation::ActionAborted(_, node_id, cause) =>
+                    state.action_outcomes.entry(node_id).or_default()
+                        .push_back((seq, ReplayedActionOutcome::Aborted(cause, seq))),
+                RecordedObservation::ActionEnded(_, node_id, result) =>
+                    state.action_outcomes.entry(node_id).or_default()
+                        .push_back((seq, ReplayedActionOutcome::Ended(result))),
+                RecordedObservation::ClockRead(now) =>
+                    state.clock_reads.push_back((seq, now)),
+                RecordedObservation::ValueChanges(values) =>
+                    state.value_changes.push_back((seq, values)),
+                RecordedObservation::ValueRead(value_name, result) =>
+                    state.value_reads.entry(value_name).or_default().push_back((seq, result)),
+                RecordedObservation::ValuesRead(value_names, result) =>
+                    state.values_reads.entry(value_names).or_default().push_back((seq, result))
+            }
+        }
+
+        ExecutionReplay {
+            state: Mutex::new(state)
+        }
+    }
+
+    fn get_divergences(&self) -> Vec<ReplayDivergence> {
+        match self.state.lock() {
+            Ok(state) => state.divergences.clone(),
+            Err(_) => Vec::new()
+        }
+    }
+
+    fn diverge(&self,
+               divergence: ReplayDivergence) {
+        if let Ok(mut state) = self.state.lock() {
+            state.divergences.push(divergence);
+        }
+    }
+
+    ///
+    /// Resolves once everything recorded before the preemption has been replayed, by then
+    /// the preempting node should have dropped the action.
+    ///
+    async fn await_preemption(&self,
+                              seq: usize) {
+        let receiver = match self.state.lock() {
+            Ok(mut state) if !state.is_replayed_before(seq) => {
+                let (sender, receiver) = oneshot::channel();
+                state.preemption_waiters.push((seq, sender));
+                Some(receiver)
+            },
+            _ => None
+        };
+
+        match receiver {
+            Some(receiver) => {
+                let _ = receiver.await;
+            },
+            None => task::yield_now().await
+        }
+    }
+
+    fn next_action_outcome(&self,
+                           node_id: &i32,
+                           context: &BTNodeExecutionContext) -> Option<ReplayedActionOutcome> {
+        self.next(context,
+                  |state| state.action_outcomes.get_mut(node_id)?.pop_front(),
+                  || ReplayDivergence::MissingActionOutcome(*node_id))
+    }
+
+    fn next_clock_read(&self,
+                       context: &BTNodeExecutionContext) -> Option<NaiveDateTime> {
+        self.next(context,
+                  |state| state.clock_reads.pop_front(),
+                  || ReplayDivergence::MissingClockRead)
+    }
+
+    fn next_value_read(&self,
+                       value_name: &String,
+                       context: &BTNodeExecutionContext)
+                       -> Option<Result<Option<ValueHolder>, LocalBlackboardError>> {
+        self.next(context,
+                  |state| state.value_reads.get_mut(value_name)?.pop_front(),
+                  || ReplayDivergence::MissingValueRead(value_name.clone()))
+    }
+
+    fn next_values_read(&self,
+                        value_names: &Vec<String>,
+                        context: &BTNodeExecutionContext)
+                        -> Option<Result<ValuesPayload, LocalBlackboardError>> {
+        self.next(context,
+                  |state| state.values_reads.get_mut(value_names)?.pop_front(),
+                  || ReplayDivergence::MissingValuesRead(value_names.clone()))
+    }
+
+    ///
+    /// Value changes recorded before the returned observation are delivered first, so that
+    /// reactive nodes observe them at the same point of the execution as during the recording.
+    ///
+    fn next<T, P, D>(&self,
+                     context: &BTNodeExecutionContext,
+                     pop: P,
+                     divergence: D) -> Option<T>
+        where P: FnOnce(&mut ReplayState) -> Option<(usize, T)>,
+              D: FnOnce() -> ReplayDivergence {
+        let (value, value_changes) = {
+            let mut state = self.state.lock().ok()?;
+
+            match pop(&mut state) {
+                None => {
+                    state.divergences.push(divergence());
+                    return None;
+                },
+                Some((seq, value)) => {
+                    let mut value_changes = Vec::new();
+
+                    while let Some((changes_seq, _)) = state.value_changes.front() {
+                        if *changes_seq > seq {
+                            break;
+                        }
+                        if let Some((changes_seq, changed)) = state.value_changes.pop_front() {
+                            state.unreplayed.remove(&changes_seq);
+                            value_changes.push(changed);
+                        }
+                    }
+
+                    state.unreplayed.remove(&seq);
+                    state.notify_preemption_waiters();
+
+                    (value, value_changes)
+                }
+            }
+        };
+
+        for changed in value_changes {
+            context.handle_value_changes(&changed);
+        }
+
+        Some(value)
+    }
+
+}
+
+impl ReplayState {
+
+    fn is_replayed_before(&self,
+                          seq: usize) -> bool {
+        self.unreplayed.range(..seq).next().is_none()
+    }
+
+    fn notify_preemption_waiters(&mut self) {
+        for (seq, sender) in std::mem::take(&mut self.preemption_waiters) {
+            if self.is_replayed_before(seq) {
+                let _ = sender.send(());
+            } else {
+                self.preemption_waiters.push((seq, sender));
+            }
+        }
+    }
+
+}
+
+fn to_key(value_names: &HashSet<String>) -> Vec<String> {
+    let mut key: Vec<String> = value_names.iter().cloned().collect();
+    key.sort();
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::FutureExt;
+
+    use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper, RelationalExpression, RelationalExpressionSpecification};
+    use buttercup_conditions::relational::EqualsRelationalExpression;
+
+    use crate::context::clock::ManualClock;
+    use crate::context::test_utils;
+    use crate::node::{BehaviorTreeNode, BTNode};
+    use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::action::wait::WaitDurationActionNode;
+    use crate::node::composite::parallel::ParallelCompositeNode;
+    use crate::node::decorator::condition::ConditionDecoratorNode;
+
+    use super::*;
+
+    const VALUE_NAME: &str = "value_name";
+
+    #[actix_rt::test]
+    async fn test_replays_recorded_execution_without_blackboard_values() {
+        let node = conditional_print_log();
+
+        let (recording, recorded_path) = {
+            let context = BTNodeExecutionContext::default();
+            context.put_values(
+                &ValuesPayload::singleton(VALUE_NAME.to_owned(), "expected".to_owned().into())).unwrap();
+
+            context.get_recording_context().start_recording().unwrap();
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.tick(&TickHeader::default(), &context).await);
+
+            (context.get_recording_context().stop_recording().unwrap(),
+             test_utils::get_path(&context))
+        };
+
+        test_utils::destroy(recorded_path);
+
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            context.get_recording_context().start_replay(&recording).unwrap();
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.tick(&TickHeader::default(), &context).await);
+            assert!(context.get_recording_context().get_replay_divergences().is_empty());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_replays_recorded_action_outcomes() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let observations = vec![
+                RecordedObservation::ValuesRead(
+                    vec![VALUE_NAME.to_owned()],
+                    Result::Ok(
+                        ValuesPayload::singleton(
+                            VALUE_NAME.to_owned(), "expected".to_owned().into()))),
+                RecordedObservation::ActionEnded(
+                    Uuid::default(), 2, Result::Ok(TickStatus::Failure))
+            ];
+
+            context.get_recording_context()
+                .start_replay(&ExecutionRecording::new(observations)).unwrap();
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       conditional_print_log().tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_ends_replay_of_interrupted_action_with_error() {
+        let node: BTNode = WaitDurationActionNode::new(1, Duration::from_secs(3600).into()).into();
+        let recording = record(&node);
+
+        assert!(recording.get_observations()
+            .iter()
+            .any(|observation| matches!(observation,
+                                        RecordedObservation::ActionAborted(_, 1, AbortCause::Interrupted))));
+
+        let path = {
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
+            context.get_recording_context().start_replay(&recording).unwrap();
+
+            assert_eq!(Result::Err(TickError::AbortedExecution(1)),
+                       node.tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_replays_action_preempted_by_tree() {
+        let node: BTNode = ParallelCompositeNode::new(
+            1,
+            vec![
+                WaitDurationActionNode::new(2, Duration::from_secs(3600).into()).into(),
+                PrintLogActionNode::new(3, "Recorded".to_owned()).into()
+            ],
+            1).unwrap().into();
+        let recording = record(&node);
+
+        assert!(recording.get_observations()
+            .iter()
+            .any(|observation| matches!(observation,
+                                        RecordedObservation::ActionAborted(_, 2, AbortCause::Preempted))));
+
+        let path = {
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
+            context.get_recording_context().start_replay(&recording).unwrap();
+
+            assert_eq!(Some(Result::Ok(TickStatus::Success)),
+                       node.tick(&TickHeader::default(), &context).now_or_never());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_ends_replay_of_preemption_which_did_not_happen_with_divergence() {
+        let path = {
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
+            let node: BTNode = WaitDurationActionNode::new(1, Duration::from_secs(3600).into()).into();
+            let observations = vec![
+                RecordedObservation::ActionAborted(Uuid::default(), 1, AbortCause::Preempted)
+            ];
+
+            context.get_recording_context()
+                .start_replay(&ExecutionRecording::new(observations)).unwrap();
+
+            assert_eq!(Result::Err(
+                           TickError::ReplayDiverged(1, ReplayDivergence::UnreplayedPreemption(1))),
+                       node.tick(&TickHeader::default(), &context).await);
+            assert!(context.get_recording_context()
+                .get_replay_divergences()
+                .contains(&ReplayDivergence::UnreplayedPreemption(1)));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_records_actions_preempted_only_by_enclosing_activations() {
+        let path = {
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
+            let node: BTNode = WaitDurationActionNode::new(1, Duration::from_secs(3600).into()).into();
+            let header = TickHeader::default().with_activation_key(ReactiveActivationKey::new(2));
+
+            context.get_recording_context().start_recording().unwrap();
+
+            let mut tick = node.tick(&header, &context).boxed();
+
+            assert!((&mut tick).now_or_never().is_none());
+
+            context.get_recording_context().preempt(&ReactiveActivationKey::new(3), tick);
+
+            assert!(context.get_recording_context()
+                .stop_recording()
+                .unwrap()
+                .get_observations()
+                .contains(&RecordedObservation::ActionAborted(
+                    Uuid::default(), 1, AbortCause::Interrupted)));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    ///
+    /// Ticks the node once, dropping it if it did not finish.
+    ///
+    fn record(node: &BTNode) -> ExecutionRecording {
+        let (recording, path) = {
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
+            context.get_recording_context().start_recording().unwrap();
+
+            let _ = node.tick(&TickHeader::default(), &context).now_or_never();
+
+            (context.get_recording_context().stop_recording().unwrap(),
+             test_utils::get_path(&context))
+        };
+
+        test_utils::destroy(path);
+
+        recording
+    }
+
+    fn conditional_print_log() -> BTNode {
+        ConditionDecoratorNode::new(
+            1,
+            PrintLogActionNode::new(2, "Recorded".to_owned()).into(),
+            ConditionExpressionWrapper::new(
+                ConditionExpression::RelationExpression(
+                    RelationalExpression::Equals(
+                        EqualsRelationalExpression::new(
+                            RelationalExpressionSpecification::NameAndLiteral(
+                                VALUE_NAME.to_owned(), "expected".to_owned().into())))))).into()
+    }
+
+}
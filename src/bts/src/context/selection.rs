@@ -0,0 +1,29 @@
+use dashmap::DashMap;
+
+///
+/// Children selected by the selector nodes in their last ticks, kept per context so that
+/// agents running the same tree do not influence each other.
+///
+#[derive(Default)]
+pub struct SelectionContext {
+
+    selected_children_by_node_ids: DashMap<i32, usize>
+
+}
+
+impl SelectionContext {
+
+    pub fn get_selected_child(&self,
+                              node_id: &i32) -> Option<usize> {
+        self.selected_children_by_node_ids
+            .get(node_id)
+            .map(|entry| *entry.value())
+    }
+
+    pub fn set_selected_child(&self,
+                              node_id: i32,
+                              child_index: usize) {
+        self.selected_children_by_node_ids.insert(node_id, child_index);
+    }
+
+}
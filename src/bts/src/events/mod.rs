@@ -1,9 +1,25 @@
-use chrono::{NaiveDateTime, Utc};
+use std::collections::HashSet;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
 use uuid::Uuid;
 
+use crate::node::decorator::reactive::DataChangeHandlingError;
 use crate::tick::{TickError, TickHeader, TickStatus};
 
-#[derive(Debug)]
+pub mod sink;
+
+#[derive(Serialize, Debug)]
+pub enum BTNodeExecutionEvent<'e> {
+
+    ExecutionStarted(&'e BTNodeExecutionStartedEvent<'e>),
+    ExecutionEnded(&'e BTNodeExecutionEndedEvent<'e>),
+    ReactiveErrorOccurred(&'e ReactiveErrorEvent<'e>),
+    ValuesChanged(&'e ValuesChangedEvent<'e>)
+
+}
+
+#[derive(Serialize, Debug)]
 pub struct BTNodeExecutionEndedEvent<'e> {
 
     id: Uuid,
@@ -39,7 +55,7 @@ impl<'e> BTNodeExecutionEndedEvent<'e> {
                took_ms: i64) -> BTNodeExecutionEndedEvent<'e> {
         BTNodeExecutionEndedEvent {
             id: Uuid::new_v4(),
-            created_at: Utc::now().naive_utc(),
+            created_at: *ended_at,
             correlation_id: tick_header.get_correlation_id(),
             node_id,
             node_tick_id,
@@ -55,7 +71,7 @@ impl<'e> BTNodeExecutionEndedEvent<'e> {
 
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Debug)]
 pub struct BTNodeExecutionStartedEvent<'e> {
 
     id: Uuid,
@@ -82,7 +98,7 @@ impl<'e> BTNodeExecutionStartedEvent<'e> {
                tick_header: &'e TickHeader) -> BTNodeExecutionStartedEvent<'e> {
         BTNodeExecutionStartedEvent {
             id: Uuid::new_v4(),
-            created_at: Utc::now().naive_utc(),
+            created_at: *started_at,
             correlation_id: tick_header.get_correlation_id(),
             node_id,
             node_tick_id,
@@ -93,4 +109,61 @@ impl<'e> BTNodeExecutionStartedEvent<'e> {
         }
     }
 
-}
\ No newline at end of file
+    pub fn get_node_id(&self) -> &i32 {
+        self.node_id
+    }
+
+}
+
+///
+/// Published when a reactive condition fails to handle a change of its values.
+///
+#[derive(Serialize, Debug)]
+pub struct ReactiveErrorEvent<'e> {
+
+    id: Uuid,
+    created_at: NaiveDateTime,
+
+    error: &'e DataChangeHandlingError
+
+}
+
+impl<'e> ReactiveErrorEvent<'e> {
+
+    pub fn new(created_at: NaiveDateTime,
+               error: &'e DataChangeHandlingError) -> ReactiveErrorEvent<'e> {
+        ReactiveErrorEvent {
+            id: Uuid::new_v4(),
+            created_at,
+            error
+        }
+    }
+
+    pub fn get_error(&self) -> &DataChangeHandlingError {
+        self.error
+    }
+
+}
+
+#[derive(Serialize, Debug)]
+pub struct ValuesChangedEvent<'e> {
+
+    id: Uuid,
+    created_at: NaiveDateTime,
+
+    value_names: &'e HashSet<String>
+
+}
+
+impl<'e> ValuesChangedEvent<'e> {
+
+    pub fn new(created_at: NaiveDateTime,
+               value_names: &'e HashSet<String>) -> ValuesChangedEvent<'e> {
+        ValuesChangedEvent {
+            id: Uuid::new_v4(),
+            created_at,
+            value_names
+        }
+    }
+
+}
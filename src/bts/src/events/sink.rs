@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use uuid::Uuid;
+
+use crate::events::BTNodeExecutionEvent;
+
+pub type BTNodeExecutionEventListener = Arc<dyn Fn(&BTNodeExecutionEvent) + Send + Sync>;
+
+///
+/// Fans out execution events of a single context to the attached listeners, e.g. a debugger
+/// streaming the active path of a running agent.
+///
+#[derive(Default)]
+pub struct BTNodeExecutionEventSink {
+
+    listeners: DashMap<Uuid, BTNodeExecutionEventListener>
+
+}
+
+impl BTNodeExecutionEventSink {
+
+    pub fn add_listener(&self,
+                        listener: BTNodeExecutionEventListener) -> Uuid {
+        let listener_id = Uuid::new_v4();
+
+        self.listeners.insert(listener_id, listener);
+
+        listener_id
+    }
+
+    pub fn remove_listener(&self,
+                           listener_id: &Uuid) -> bool {
+        self.listeners.remove(listener_id).is_some()
+    }
+
+    pub fn has_listeners(&self) -> bool {
+        !self.listeners.is_empty()
+    }
+
+    ///
+    /// Listeners are called outside of the map, so that they may attach or detach listeners.
+    ///
+    pub fn publish(&self,
+                   event: &BTNodeExecutionEvent) {
+        let listeners: Vec<BTNodeExecutionEventListener> = self.listeners
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+
+        for listener in listeners {
+            listener(event);
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use chrono::Utc;
+
+    use crate::events::ValuesChangedEvent;
+
+    use super::*;
+
+    #[test]
+    fn test_publishes_only_to_attached_listeners() {
+        let sink = BTNodeExecutionEventSink::default();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_ref = counter.clone();
+
+        let listener_id = sink.add_listener(
+            Arc::new(move |_| { counter_ref.fetch_add(1, Ordering::SeqCst); }));
+
+        let value_names: HashSet<String> = HashSet::new();
+        let event = ValuesChangedEvent::new(Utc::now().naive_utc(), &value_names);
+
+        sink.publish(&BTNodeExecutionEvent::ValuesChanged(&event));
+        assert!(sink.remove_listener(&listener_id));
+        sink.publish(&BTNodeExecutionEvent::ValuesChanged(&event));
+
+        assert_eq!(1, counter.load(Ordering::SeqCst));
+        assert!(!sink.has_listeners());
+    }
+
+    #[test]
+    fn test_lets_listener_detach_itself_while_published() {
+        let sink = Arc::new(BTNodeExecutionEventSink::default());
+        let listener_id: Arc<Mutex<Option<Uuid>>> = Arc::new(Mutex::new(None));
+
+        let sink_ref = sink.clone();
+        let listener_id_ref = listener_id.clone();
+
+        *listener_id.lock().unwrap() = Some(sink.add_listener(Arc::new(move |_| {
+            if let Some(listener_id) = listener_id_ref.lock().unwrap().take() {
+                sink_ref.remove_listener(&listener_id);
+            }
+        })));
+
+        let value_names: HashSet<String> = HashSet::new();
+        let event = ValuesChangedEvent::new(Utc::now().naive_utc(), &value_names);
+
+        sink.publish(&BTNodeExecutionEvent::ValuesChanged(&event));
+
+        assert!(!sink.has_listeners());
+    }
+
+}
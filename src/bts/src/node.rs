@@ -3,7 +3,6 @@ use std::future::Future;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -48,7 +47,11 @@ pub trait BehaviorTreeNode {
         let node_id = self.get_id();
         let node_tick_id = Uuid::new_v4();
 
-        let started_at = Utc::now().naive_utc();
+        context.get_debug_context()
+            .before_tick(node_id, &node_tick_id, header, context.get_clock().as_ref())
+            .await;
+
+        let started_at = context.now();
 
         context.consume_execution_started_event(
             BTNodeExecutionStartedEvent::new(
@@ -57,7 +60,7 @@ pub trait BehaviorTreeNode {
 
         let result = self.do_tick(header, context).await;
 
-        let ended_at = Utc::now().naive_utc();
+        let ended_at = context.now();
         let took_ms = ended_at.signed_duration_since(started_at).num_milliseconds();
 
         context.consume_execution_ended_event(
@@ -93,6 +96,47 @@ impl BehaviorTreeNode for BTNode {
     }
 }
 
+///
+/// Node guarded by a condition, which reactive nodes re-evaluate while other nodes are running.
+///
+pub trait ConditionalNode: Send + Sync {
+
+    fn get_condition_value_names(&self) -> HashSet<String>;
+
+    fn is_condition_met(&self,
+                        context: &BTNodeExecutionContext) -> Result<bool, TickError>;
+
+}
+
+///
+/// Reads the values the condition refers to and applies its predicate to them.
+///
+pub fn check_condition(node_id: i32,
+                       value_names: &HashSet<String>,
+                       predicate: &(dyn Fn(&ValuesPayload) -> bool + Send + Sync),
+                       context: &BTNodeExecutionContext) -> Result<bool, TickError> {
+    match context.get_values(value_names) {
+        Ok(payload) => Result::Ok(predicate(&payload)),
+        Err(err) => Result::Err(TickError::BlackboardError(node_id, err))
+    }
+}
+
+impl BTNode {
+
+    pub fn as_conditional(&self) -> Option<&dyn ConditionalNode> {
+        match self {
+            BTNode::Action(ActionBTNode::WaitUntil(node)) => Some(node),
+            BTNode::Composite(CompositeBTNode::PrioritySelector(node)) => node.as_conditional(),
+            BTNode::Composite(CompositeBTNode::ReactiveFallback(node)) => node.as_conditional(),
+            BTNode::Composite(CompositeBTNode::ReactiveSequence(node)) => node.as_conditional(),
+            BTNode::Decorator(DecoratorBTNode::Condition(node)) => Some(node),
+            BTNode::Decorator(DecoratorBTNode::ReactiveCondition(node)) => Some(node),
+            _ => None
+        }
+    }
+
+}
+
 impl From<ActionBTNode> for BTNode {
     fn from(node: ActionBTNode) -> Self {
         BTNode::Action(node)
@@ -6,7 +6,7 @@ use crate::context::BTNodeExecutionContext;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::action::logging::PrintLogActionNode;
 use crate::node::action::subtree::ExecuteSubTreeActionNode;
-use crate::node::action::wait::WaitDurationActionNode;
+use crate::node::action::wait::{WaitDurationActionNode, WaitUntilActionNode};
 use crate::tick::{TickError, TickHeader, TickStatus};
 
 pub mod logging;
@@ -19,7 +19,8 @@ pub enum ActionBTNode {
 
     ExecuteSubTree(ExecuteSubTreeActionNode),
     PrintLog(PrintLogActionNode),
-    WaitDuration(WaitDurationActionNode)
+    WaitDuration(WaitDurationActionNode),
+    WaitUntil(WaitUntilActionNode)
 
 }
 
@@ -33,9 +34,17 @@ impl BehaviorTreeNode for ActionBTNode {
             ActionBTNode::ExecuteSubTree(node) =>
                 node.do_tick(header, context).await,
             ActionBTNode::PrintLog(node) =>
-                node.do_tick(header, context).await,
+                context.get_recording_context()
+                    .observe_action(node.get_id(), header, context, node.do_tick(header, context))
+                    .await,
             ActionBTNode::WaitDuration(node) =>
-                node.do_tick(header, context).await,
+                context.get_recording_context()
+                    .observe_action(node.get_id(), header, context, node.do_tick(header, context))
+                    .await,
+            ActionBTNode::WaitUntil(node) =>
+                context.get_recording_context()
+                    .observe_action(node.get_id(), header, context, node.do_tick(header, context))
+                    .await,
         }
     }
 
@@ -44,6 +53,7 @@ impl BehaviorTreeNode for ActionBTNode {
             ActionBTNode::ExecuteSubTree(node) => node.get_id(),
             ActionBTNode::PrintLog(node) => node.get_id(),
             ActionBTNode::WaitDuration(node) => node.get_id(),
+            ActionBTNode::WaitUntil(node) => node.get_id(),
         }
     }
 }
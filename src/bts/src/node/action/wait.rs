@@ -1,17 +1,16 @@
-use std::convert::TryInto;
+use std::collections::HashSet;
 use std::ops::Deref;
-use std::rc::Rc;
 use std::time::Duration;
 
-use async_std::task;
 use async_trait::async_trait;
+use futures::future::{self, Either};
 
-use buttercup_blackboards::LocalBlackboardError;
-use buttercup_values::ValueHolder;
-use buttercup_variables::{VariableSpecification, VariableValueAccessError};
+use buttercup_conditions::ConditionExpressionWrapper;
+use buttercup_values::ValuesPayload;
+use buttercup_variables::VariableSpecification;
 
 use crate::context::BTNodeExecutionContext;
-use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::{BehaviorTreeNode, BTNode, check_condition, ConditionalNode};
 use crate::node::action::ActionBTNode;
 use crate::tick::{TickError, TickHeader, TickStatus};
 
@@ -57,7 +56,7 @@ impl BehaviorTreeNode for WaitDurationActionNode {
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
         match self.duration.get_value(context) {
             Ok(duration) => {
-                task::sleep(duration.deref().clone()).await;
+                context.sleep(*duration.deref()).await;
                 Result::Ok(TickStatus::Success)
             }
             Err(err) =>
@@ -75,3 +74,197 @@ impl From<WaitDurationActionNode> for BTNode {
         BTNode::Action(ActionBTNode::WaitDuration(node))
     }
 }
+
+///
+/// Succeeds once the condition is met, or fails when it is not met before the timeout.
+/// In between it is suspended, woken up only by changes of the values of the condition.
+///
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct WaitUntilActionNode {
+
+    id: i32,
+
+    #[derivative(Debug="ignore")]
+    predicate: Box<dyn Fn(&ValuesPayload) -> bool + Send + Sync>,
+
+    #[derivative(Debug(format_with="WaitDurationActionNode::fmt"))]
+    timeout: VariableSpecification<Duration>,
+
+    value_names: HashSet<String>
+
+}
+
+impl WaitUntilActionNode {
+
+    pub fn new(id: i32,
+               condition: ConditionExpressionWrapper,
+               timeout: VariableSpecification<Duration>) -> WaitUntilActionNode {
+        let value_names = condition.get_value_names_cloned();
+        WaitUntilActionNode {
+            id,
+            predicate: condition.unpack(),
+            timeout,
+            value_names
+        }
+    }
+
+}
+
+impl ConditionalNode for WaitUntilActionNode {
+
+    fn get_condition_value_names(&self) -> HashSet<String> {
+        self.value_names.clone()
+    }
+
+    fn is_condition_met(&self,
+                        context: &BTNodeExecutionContext) -> Result<bool, TickError> {
+        check_condition(self.id, &self.value_names, self.predicate.deref(), context)
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for WaitUntilActionNode {
+
+    async fn do_tick(&self,
+                     _: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let timeout = match self.timeout.get_value(context) {
+            Ok(timeout) => *timeout,
+            Err(err) => return Result::Err(TickError::VariableValueAccessError(self.id, err))
+        };
+
+        let mut timeout = Box::pin(context.sleep(timeout));
+
+        loop {
+            let changed = context.get_reactive_service().watch(self.value_names.clone());
+
+            if self.is_condition_met(context)? {
+                return Result::Ok(TickStatus::Success);
+            }
+
+            if let Either::Right(_) = future::select(changed, &mut timeout).await {
+                return Result::Ok(TickStatus::Failure);
+            }
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<WaitUntilActionNode> for BTNode {
+    fn from(node: WaitUntilActionNode) -> Self {
+        BTNode::Action(ActionBTNode::WaitUntil(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::FutureExt;
+
+    use buttercup_conditions::{ConditionExpression, RelationalExpression, RelationalExpressionSpecification};
+    use buttercup_conditions::relational::EqualsRelationalExpression;
+
+    use crate::context::clock::ManualClock;
+    use crate::context::test_utils;
+
+    use super::*;
+
+    const VALUE_NAME: &str = "state";
+
+    #[actix_rt::test]
+    async fn test_waits_for_the_clock_to_advance() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = WaitDurationActionNode::new(1, Duration::from_secs(24 * 3600).into());
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+
+                clock.advance(Duration::from_secs(24 * 3600));
+
+                assert_eq!(Some(Result::Ok(TickStatus::Success)), tick.now_or_never());
+            }
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_waits_until_condition_is_met() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = wait_until_ready();
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, context.get_reactive_service().get_watchers_count());
+
+                let changed = ValuesPayload::singleton(VALUE_NAME.to_owned(), "ready".into());
+
+                context.put_values(&changed).unwrap();
+                context.handle_value_changes(&changed);
+
+                assert_eq!(Some(Result::Ok(TickStatus::Success)), tick.now_or_never());
+                assert_eq!(0, context.get_reactive_service().get_watchers_count());
+            }
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_fails_when_condition_is_not_met_before_timeout() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = wait_until_ready();
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+
+                clock.advance(Duration::from_secs(60));
+
+                assert_eq!(Some(Result::Ok(TickStatus::Failure)), tick.now_or_never());
+                assert_eq!(0, context.get_reactive_service().get_watchers_count());
+            }
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    fn wait_until_ready() -> WaitUntilActionNode {
+        WaitUntilActionNode::new(
+            1,
+            ConditionExpressionWrapper::new(
+                ConditionExpression::RelationExpression(
+                    RelationalExpression::Equals(
+                        EqualsRelationalExpression::new(
+                            RelationalExpressionSpecification::NameAndLiteral(
+                                VALUE_NAME.to_owned(), "ready".into()))))),
+            Duration::from_secs(60).into())
+    }
+
+}
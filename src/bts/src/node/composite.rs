@@ -6,12 +6,18 @@ use crate::context::BTNodeExecutionContext;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::composite::fallback::FallbackCompositeNode;
 use crate::node::composite::parallel::ParallelCompositeNode;
+use crate::node::composite::priority::PrioritySelectorCompositeNode;
+use crate::node::composite::reactive::{ReactiveFallbackCompositeNode, ReactiveSequenceCompositeNode};
 use crate::node::composite::sequence::SequenceCompositeNode;
+use crate::node::composite::utility::UtilitySelectorCompositeNode;
 use crate::tick::{TickError, TickHeader, TickStatus};
 
 pub mod parallel;
 pub mod fallback;
+pub mod priority;
+pub mod reactive;
 pub mod sequence;
+pub mod utility;
 
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -19,7 +25,11 @@ pub enum CompositeBTNode {
 
     Parallel(ParallelCompositeNode),
     Fallback(FallbackCompositeNode),
-    Sequence(SequenceCompositeNode)
+    PrioritySelector(PrioritySelectorCompositeNode),
+    ReactiveFallback(ReactiveFallbackCompositeNode),
+    ReactiveSequence(ReactiveSequenceCompositeNode),
+    Sequence(SequenceCompositeNode),
+    UtilitySelector(UtilitySelectorCompositeNode)
 
 }
 
@@ -34,8 +44,16 @@ impl BehaviorTreeNode for CompositeBTNode {
                 node.do_tick(header, context).await,
             CompositeBTNode::Fallback(node) =>
                 node.do_tick(header, context).await,
+            CompositeBTNode::PrioritySelector(node) =>
+                node.do_tick(header, context).await,
+            CompositeBTNode::ReactiveFallback(node) =>
+                node.do_tick(header, context).await,
+            CompositeBTNode::ReactiveSequence(node) =>
+                node.do_tick(header, context).await,
             CompositeBTNode::Sequence(node) =>
                 node.do_tick(header, context).await,
+            CompositeBTNode::UtilitySelector(node) =>
+                node.do_tick(header, context).await,
         }
     }
 
@@ -43,7 +61,11 @@ impl BehaviorTreeNode for CompositeBTNode {
         match self {
             CompositeBTNode::Parallel(node) => node.get_id(),
             CompositeBTNode::Fallback(node) => node.get_id(),
+            CompositeBTNode::PrioritySelector(node) => node.get_id(),
+            CompositeBTNode::ReactiveFallback(node) => node.get_id(),
+            CompositeBTNode::ReactiveSequence(node) => node.get_id(),
             CompositeBTNode::Sequence(node) => node.get_id(),
+            CompositeBTNode::UtilitySelector(node) => node.get_id(),
         }
     }
 }
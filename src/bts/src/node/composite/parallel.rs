@@ -6,6 +6,7 @@ use async_trait::async_trait;
 use futures::future::select_all;
 
 use crate::context::BTNodeExecutionContext;
+use crate::context::reactive::ReactiveActivationKey;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::composite::CompositeBTNode;
 use crate::tick::{TickError, TickStatus, TickHeader};
@@ -56,10 +57,12 @@ impl BehaviorTreeNode for ParallelCompositeNode {
     async fn do_tick(&self,
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let activation_key = ReactiveActivationKey::new(self.id);
+        let child_header = header.with_activation_key(activation_key.clone());
         let mut futures = Vec::new();
 
         for child in &self.children {
-            futures.push(child.tick(header, context));
+            futures.push(child.tick(&child_header, context));
         }
 
         let mut num_failures: usize = 0;
@@ -88,10 +91,14 @@ impl BehaviorTreeNode for ParallelCompositeNode {
             }
 
             if num_successes >= self.num_successes_to_succeed {
+                context.get_recording_context().preempt(&activation_key, futures);
+
                 return Result::Ok(TickStatus::Success);
             }
 
             if num_failures >= self.num_failures_to_fail {
+                context.get_recording_context().preempt(&activation_key, futures);
+
                 if errors.is_empty() {
                     return Result::Ok(TickStatus::Failure);
                 }
@@ -119,6 +126,7 @@ mod tests {
 
     use actix_web::test;
 
+    use crate::context::clock::ManualClock;
     use crate::context::test_utils;
     use crate::node::action::logging::PrintLogActionNode;
     use crate::node::action::wait::WaitDurationActionNode;
@@ -128,11 +136,11 @@ mod tests {
     #[actix_rt::test]
     async fn test_finishes_based_on_minimal_number_of_successes() {
         let path = {
-            let context = Default::default();
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
             let children: Vec<BTNode> = vec![
                 PrintLogActionNode::new(1, "I am one.".to_string()).into(),
                 WaitDurationActionNode::new(2,
-                                            Duration::from_millis(10).into()).into(),
+                                            Duration::from_secs(3600).into()).into(),
                 PrintLogActionNode::new(3, "I am two.".to_string()).into(),
                 PrintLogActionNode::new(4, "I am four.".to_string()).into()];
             match ParallelCompositeNode::new(5, children, 3)
@@ -0,0 +1,399 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+
+use buttercup_variables::VariableSpecification;
+
+use crate::context::BTNodeExecutionContext;
+use crate::node::{BehaviorTreeNode, BTNode, ConditionalNode};
+use crate::node::composite::CompositeBTNode;
+use crate::node::composite::reactive::{self, GuardedTickResult};
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+///
+/// Fallback over children ordered by their priorities, highest first. While a child is running,
+/// a higher priority child whose condition becomes met preempts it.
+///
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct PrioritySelectorCompositeNode {
+
+    id: i32,
+    children: Vec<PrioritizedChild>
+
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct PrioritizedChild {
+
+    #[derivative(Debug="ignore")]
+    priority: VariableSpecification<i64>,
+    node: BTNode
+
+}
+
+impl PrioritizedChild {
+
+    pub fn new(priority: VariableSpecification<i64>,
+               node: BTNode) -> PrioritizedChild {
+        PrioritizedChild {
+            priority,
+            node
+        }
+    }
+
+}
+
+impl PrioritySelectorCompositeNode {
+
+    pub fn new(id: i32,
+               children: Vec<PrioritizedChild>) -> PrioritySelectorCompositeNode {
+        PrioritySelectorCompositeNode {
+            id,
+            children
+        }
+    }
+
+    ///
+    /// The selector can make progress when any of its conditional children can.
+    ///
+    pub fn as_conditional(&self) -> Option<&dyn ConditionalNode> {
+        if self.children.iter().any(|child| child.node.as_conditional().is_some()) {
+            return Some(self);
+        }
+
+        None
+    }
+
+    ///
+    /// Indices of the children, ordered by descending priority and then by position.
+    ///
+    fn get_order(&self,
+                 context: &BTNodeExecutionContext) -> Result<Vec<usize>, TickError> {
+        let mut priorities = Vec::with_capacity(self.children.len());
+
+        for (index, child) in self.children.iter().enumerate() {
+            let priority = child.priority
+                .get_value(context)
+                .map_err(|err| TickError::VariableValueAccessError(self.id, err))?;
+
+            priorities.push((*priority, index));
+        }
+
+        priorities.sort_by(|(left, left_index), (right, right_index)|
+            right.cmp(left).then(left_index.cmp(right_index)));
+
+        Result::Ok(priorities.into_iter().map(|(_, index)| index).collect())
+    }
+
+    fn find_preempting(&self,
+                       running_index: usize,
+                       context: &BTNodeExecutionContext) -> Result<Option<usize>, TickError> {
+        for index in self.get_order(context)? {
+            if index == running_index {
+                break;
+            }
+
+            if let Some(conditional) = self.children[index].node.as_conditional() {
+                if conditional.is_condition_met(context)? {
+                    return Result::Ok(Some(index));
+                }
+            }
+        }
+
+        Result::Ok(None)
+    }
+
+    fn get_priority_value_names(&self) -> HashSet<String> {
+        self.children
+            .iter()
+            .filter_map(|child| match &child.priority {
+                VariableSpecification::VariableName(name) => Some(name.get_value().clone()),
+                VariableSpecification::Literal(_) => None
+            })
+            .collect()
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for PrioritySelectorCompositeNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let priority_value_names = self.get_priority_value_names();
+        let mut order = self.get_order(context)?;
+        let mut position = 0;
+
+        while position < order.len() {
+            let index = order[position];
+
+            let mut value_names = priority_value_names.clone();
+            value_names.extend(
+                self.children
+                    .iter()
+                    .enumerate()
+                    .filter(|(other_index, _)| *other_index != index)
+                    .filter_map(|(_, child)| child.node.as_conditional())
+                    .flat_map(|conditional| conditional.get_condition_value_names()));
+
+            match reactive::tick_guarded(self.id,
+                                         &self.children[index].node,
+                                         value_names,
+                                         header,
+                                         context,
+                                         |context| self.find_preempting(index, context)).await? {
+                GuardedTickResult::Completed(Ok(TickStatus::Failure)) => position += 1,
+                GuardedTickResult::Completed(result) => return result,
+                GuardedTickResult::Aborted => return Result::Ok(TickStatus::Failure),
+                GuardedTickResult::Preempted(preempting_index) => {
+                    order = self.get_order(context)?;
+                    position = order
+                        .iter()
+                        .position(|index| *index == preempting_index)
+                        .unwrap_or(0);
+                }
+            }
+        }
+
+        Result::Ok(TickStatus::Failure)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl ConditionalNode for PrioritySelectorCompositeNode {
+
+    fn get_condition_value_names(&self) -> HashSet<String> {
+        self.children
+            .iter()
+            .filter_map(|child| child.node.as_conditional())
+            .flat_map(|conditional| conditional.get_condition_value_names())
+            .collect()
+    }
+
+    fn is_condition_met(&self,
+                        context: &BTNodeExecutionContext) -> Result<bool, TickError> {
+        for child in &self.children {
+            if let Some(conditional) = child.node.as_conditional() {
+                if conditional.is_condition_met(context)? {
+                    return Result::Ok(true);
+                }
+            }
+        }
+
+        Result::Ok(false)
+    }
+
+}
+
+impl From<PrioritySelectorCompositeNode> for BTNode {
+    fn from(node: PrioritySelectorCompositeNode) -> Self {
+        BTNode::Composite(CompositeBTNode::PrioritySelector(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::FutureExt;
+    use num::BigInt;
+
+    use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper, RelationalExpression, RelationalExpressionSpecification};
+    use buttercup_conditions::relational::EqualsRelationalExpression;
+    use buttercup_values::{ValueHolder, ValuesPayload};
+    use buttercup_variables::VariableName;
+
+    use crate::context::clock::ManualClock;
+    use crate::context::recording::{AbortCause, RecordedObservation};
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::action::wait::WaitDurationActionNode;
+    use crate::node::decorator::condition::ConditionDecoratorNode;
+
+    use super::*;
+
+    const ALERT: &str = "alert";
+    const CRAWLING_PRIORITY: &str = "crawling_priority";
+
+    #[actix_rt::test]
+    async fn test_preempts_running_child_when_higher_priority_condition_is_met() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = PrioritySelectorCompositeNode::new(
+                1,
+                vec![
+                    PrioritizedChild::new(1.into(), crawl(2)),
+                    PrioritizedChild::new(10.into(), handle_alert(4))
+                ]);
+
+            set(&context, ALERT, ValueHolder::Boolean(false));
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, clock.get_sleepers_count());
+
+                set(&context, ALERT, ValueHolder::Boolean(true));
+
+                assert_eq!(Some(Result::Ok(TickStatus::Success)), tick.now_or_never());
+            }
+
+            assert_eq!(0, clock.get_sleepers_count());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_orders_children_by_blackboard_priorities() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = PrioritySelectorCompositeNode::new(
+                1,
+                vec![
+                    PrioritizedChild::new(
+                        VariableName::new(CRAWLING_PRIORITY.to_owned()).into(), crawl(2)),
+                    PrioritizedChild::new(10.into(), handle_alert(4))
+                ]);
+
+            set(&context, ALERT, ValueHolder::Boolean(true));
+            set(&context, CRAWLING_PRIORITY, ValueHolder::Integer(BigInt::from(100)));
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, clock.get_sleepers_count());
+            }
+
+            set(&context, CRAWLING_PRIORITY, ValueHolder::Integer(BigInt::from(1)));
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.tick(&TickHeader::default(), &context).await);
+            assert_eq!(0, clock.get_sleepers_count());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_registers_running_child_as_abortable_activation() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = PrioritySelectorCompositeNode::new(
+                1,
+                vec![
+                    PrioritizedChild::new(1.into(), crawl(2)),
+                    PrioritizedChild::new(10.into(), handle_alert(4))
+                ]);
+
+            set(&context, ALERT, ValueHolder::Boolean(false));
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, context.get_reactive_service().get_activations_count());
+
+                context.get_reactive_service().clear();
+
+                assert_eq!(Some(Result::Ok(TickStatus::Failure)), tick.now_or_never());
+            }
+
+            assert_eq!(0, context.get_reactive_service().get_activations_count());
+            assert_eq!(0, clock.get_sleepers_count());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_reports_failed_preemption_checks() {
+        let path = {
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
+            let node = PrioritySelectorCompositeNode::new(
+                1,
+                vec![
+                    PrioritizedChild::new(
+                        VariableName::new(CRAWLING_PRIORITY.to_owned()).into(), crawl(2)),
+                    PrioritizedChild::new(10.into(), handle_alert(4))
+                ]);
+
+            set(&context, ALERT, ValueHolder::Boolean(false));
+            set(&context, CRAWLING_PRIORITY, ValueHolder::Integer(BigInt::from(1)));
+
+            context.get_recording_context().start_recording().unwrap();
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+
+                set(&context, CRAWLING_PRIORITY, ValueHolder::Boolean(true));
+
+                assert!(matches!(tick.now_or_never(), Some(Result::Err(_))));
+            }
+
+            assert_eq!(1, context.get_metrics().get_reactive_errors_count_by_node_id(&1));
+            assert_eq!(0, context.get_reactive_service().get_activations_count());
+            assert!(context.get_recording_context()
+                .stop_recording()
+                .unwrap()
+                .get_observations()
+                .iter()
+                .any(|observation| matches!(observation,
+                                            RecordedObservation::ActionAborted(_, 2, AbortCause::Preempted))));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    fn crawl(id: i32) -> BTNode {
+        WaitDurationActionNode::new(id, Duration::from_secs(3600).into()).into()
+    }
+
+    fn handle_alert(id: i32) -> BTNode {
+        ConditionDecoratorNode::new(
+            id,
+            PrintLogActionNode::new(id + 1, "Handling alert.".to_owned()).into(),
+            ConditionExpressionWrapper::new(
+                ConditionExpression::RelationExpression(
+                    RelationalExpression::Equals(
+                        EqualsRelationalExpression::new(
+                            RelationalExpressionSpecification::NameAndLiteral(
+                                ALERT.to_owned(), ValueHolder::Boolean(true))))))).into()
+    }
+
+    fn set(context: &BTNodeExecutionContext,
+           value_name: &str,
+           value: ValueHolder) {
+        let changed = ValuesPayload::singleton(value_name.to_owned(), value);
+
+        context.put_values(&changed).unwrap();
+        context.handle_value_changes(&changed);
+    }
+
+}
@@ -0,0 +1,392 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::{self, Abortable, AbortHandle, Either};
+
+use crate::context::BTNodeExecutionContext;
+use crate::context::reactive::ReactiveActivationKey;
+use crate::node::{BehaviorTreeNode, BTNode, ConditionalNode};
+use crate::node::composite::CompositeBTNode;
+use crate::node::decorator::reactive::{DataChangeHandlingError, ReactiveConditionInnerNode};
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+///
+/// Sequence which, while a child is running, re-evaluates the conditions of the earlier children
+/// on every change of their values and fails, preempting the running child, once one is not met.
+///
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ReactiveSequenceCompositeNode {
+
+    id: i32,
+    children: Vec<BTNode>
+
+}
+
+///
+/// Fallback which, while a child is running, re-evaluates the conditions of the earlier children
+/// on every change of their values and preempts the running child once one of them is met,
+/// continuing from that child.
+///
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ReactiveFallbackCompositeNode {
+
+    id: i32,
+    children: Vec<BTNode>
+
+}
+
+pub(crate) enum GuardedTickResult {
+
+    Aborted,
+    Completed(Result<TickStatus, TickError>),
+    Preempted(usize)
+
+}
+
+impl ReactiveSequenceCompositeNode {
+
+    pub fn new(id: i32,
+               children: Vec<BTNode>) -> ReactiveSequenceCompositeNode {
+        ReactiveSequenceCompositeNode {
+            id,
+            children
+        }
+    }
+
+    ///
+    /// The sequence can only make progress when its first child can.
+    ///
+    pub fn as_conditional(&self) -> Option<&dyn ConditionalNode> {
+        self.children.first().and_then(BTNode::as_conditional)
+    }
+
+    fn find_unmet(earlier: &[BTNode],
+                  context: &BTNodeExecutionContext) -> Result<Option<usize>, TickError> {
+        for (index, child) in earlier.iter().enumerate() {
+            if let Some(conditional) = child.as_conditional() {
+                if !conditional.is_condition_met(context)? {
+                    return Result::Ok(Some(index));
+                }
+            }
+        }
+
+        Result::Ok(None)
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for ReactiveSequenceCompositeNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        for (index, child) in self.children.iter().enumerate() {
+            let earlier = &self.children[..index];
+
+            match tick_guarded(self.id,
+                               child,
+                               get_value_names(earlier),
+                               header,
+                               context,
+                               |context| ReactiveSequenceCompositeNode::find_unmet(
+                                   earlier, context)).await? {
+                GuardedTickResult::Completed(Ok(TickStatus::Success)) => {},
+                GuardedTickResult::Completed(result) => return result,
+                GuardedTickResult::Aborted | GuardedTickResult::Preempted(_) =>
+                    return Result::Ok(TickStatus::Failure)
+            }
+        }
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<ReactiveSequenceCompositeNode> for BTNode {
+    fn from(node: ReactiveSequenceCompositeNode) -> Self {
+        BTNode::Composite(CompositeBTNode::ReactiveSequence(node))
+    }
+}
+
+impl ReactiveFallbackCompositeNode {
+
+    pub fn new(id: i32,
+               children: Vec<BTNode>) -> ReactiveFallbackCompositeNode {
+        ReactiveFallbackCompositeNode {
+            id,
+            children
+        }
+    }
+
+    ///
+    /// The fallback can make progress when any of its conditional children can.
+    ///
+    pub fn as_conditional(&self) -> Option<&dyn ConditionalNode> {
+        if self.children.iter().any(|child| child.as_conditional().is_some()) {
+            return Some(self);
+        }
+
+        None
+    }
+
+    fn find_met(earlier: &[BTNode],
+                context: &BTNodeExecutionContext) -> Result<Option<usize>, TickError> {
+        for (index, child) in earlier.iter().enumerate() {
+            if let Some(conditional) = child.as_conditional() {
+                if conditional.is_condition_met(context)? {
+                    return Result::Ok(Some(index));
+                }
+            }
+        }
+
+        Result::Ok(None)
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for ReactiveFallbackCompositeNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let mut index = 0;
+
+        while index < self.children.len() {
+            let earlier = &self.children[..index];
+
+            match tick_guarded(self.id,
+                               &self.children[index],
+                               get_value_names(earlier),
+                               header,
+                               context,
+                               |context| ReactiveFallbackCompositeNode::find_met(
+                                   earlier, context)).await? {
+                GuardedTickResult::Completed(Ok(TickStatus::Failure)) => index += 1,
+                GuardedTickResult::Completed(result) => return result,
+                GuardedTickResult::Aborted => return Result::Ok(TickStatus::Failure),
+                GuardedTickResult::Preempted(preempting_index) => index = preempting_index
+            }
+        }
+
+        Result::Ok(TickStatus::Failure)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl ConditionalNode for ReactiveFallbackCompositeNode {
+
+    fn get_condition_value_names(&self) -> HashSet<String> {
+        get_value_names(&self.children)
+    }
+
+    fn is_condition_met(&self,
+                        context: &BTNodeExecutionContext) -> Result<bool, TickError> {
+        Result::Ok(ReactiveFallbackCompositeNode::find_met(&self.children, context)?.is_some())
+    }
+
+}
+
+impl From<ReactiveFallbackCompositeNode> for BTNode {
+    fn from(node: ReactiveFallbackCompositeNode) -> Self {
+        BTNode::Composite(CompositeBTNode::ReactiveFallback(node))
+    }
+}
+
+///
+/// Ticks the child, looking for a preempting child on every change of the given values.
+/// The next watch is created before looking, so that no change is missed in between.
+/// The execution is registered as an activation of the node, so that it can be aborted.
+///
+pub(crate) async fn tick_guarded<F>(node_id: i32,
+                                    child: &BTNode,
+                                    value_names: HashSet<String>,
+                                    header: &TickHeader,
+                                    context: &BTNodeExecutionContext,
+                                    find_preempting: F) -> Result<GuardedTickResult, TickError>
+    where F: Fn(&BTNodeExecutionContext) -> Result<Option<usize>, TickError> {
+    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+    let activation_key = ReactiveActivationKey::new(node_id);
+
+    let _registration = context.get_reactive_service().register(
+        abort_handle,
+        activation_key.clone(),
+        &Arc::new(ReactiveConditionInnerNode::guarding(node_id)));
+
+    let child_header = header.with_activation_key(activation_key.clone());
+    let mut child_tick = Abortable::new(child.tick(&child_header, context), abort_registration);
+
+    if value_names.is_empty() {
+        return match (&mut child_tick).await {
+            Ok(result) => Result::Ok(GuardedTickResult::Completed(result)),
+            Err(_) => {
+                context.get_recording_context().preempt(&activation_key, child_tick);
+
+                Result::Ok(GuardedTickResult::Aborted)
+            }
+        };
+    }
+
+    let mut changed = context.get_reactive_service().watch(value_names.clone());
+
+    loop {
+        match future::select(&mut child_tick, changed).await {
+            Either::Left((Ok(result), _)) =>
+                return Result::Ok(GuardedTickResult::Completed(result)),
+            Either::Left((Err(_), _)) => {
+                context.get_recording_context().preempt(&activation_key, child_tick);
+
+                return Result::Ok(GuardedTickResult::Aborted);
+            },
+            Either::Right(_) => {
+                changed = context.get_reactive_service().watch(value_names.clone());
+
+                match find_preempting(context) {
+                    Ok(None) => {},
+                    Ok(Some(index)) => {
+                        context.get_recording_context().preempt(&activation_key, child_tick);
+
+                        return Result::Ok(GuardedTickResult::Preempted(index));
+                    },
+                    Err(err) => {
+                        context.get_recording_context().preempt(&activation_key, child_tick);
+
+                        context.report_reactive_error(
+                            &DataChangeHandlingError::PreemptionCheckError(node_id, err.clone()));
+
+                        return Result::Err(err);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn get_value_names(children: &[BTNode]) -> HashSet<String> {
+    children
+        .iter()
+        .filter_map(BTNode::as_conditional)
+        .flat_map(|conditional| conditional.get_condition_value_names())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::FutureExt;
+
+    use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper, RelationalExpression, RelationalExpressionSpecification};
+    use buttercup_conditions::relational::EqualsRelationalExpression;
+    use buttercup_values::ValuesPayload;
+
+    use crate::context::clock::ManualClock;
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::action::wait::WaitDurationActionNode;
+    use crate::node::decorator::condition::ConditionDecoratorNode;
+
+    use super::*;
+
+    const VALUE_NAME: &str = "state";
+
+    #[actix_rt::test]
+    async fn test_sequence_preempts_running_child_when_earlier_condition_is_not_met() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = ReactiveSequenceCompositeNode::new(
+                1,
+                vec![
+                    when_ready(2),
+                    WaitDurationActionNode::new(4, Duration::from_secs(3600).into()).into()
+                ]);
+
+            set_state(&context, "ready");
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, clock.get_sleepers_count());
+
+                set_state(&context, "stopped");
+
+                assert_eq!(Some(Result::Ok(TickStatus::Failure)), tick.now_or_never());
+            }
+
+            assert_eq!(0, clock.get_sleepers_count());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_fallback_preempts_running_child_when_earlier_condition_is_met() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = ReactiveFallbackCompositeNode::new(
+                1,
+                vec![
+                    when_ready(2),
+                    WaitDurationActionNode::new(4, Duration::from_secs(3600).into()).into()
+                ]);
+
+            set_state(&context, "stopped");
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, clock.get_sleepers_count());
+
+                set_state(&context, "ready");
+
+                assert_eq!(Some(Result::Ok(TickStatus::Success)), tick.now_or_never());
+            }
+
+            assert_eq!(0, clock.get_sleepers_count());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    fn when_ready(id: i32) -> BTNode {
+        ConditionDecoratorNode::new(
+            id,
+            PrintLogActionNode::new(id + 1, "Ready.".to_owned()).into(),
+            ConditionExpressionWrapper::new(
+                ConditionExpression::RelationExpression(
+                    RelationalExpression::Equals(
+                        EqualsRelationalExpression::new(
+                            RelationalExpressionSpecification::NameAndLiteral(
+                                VALUE_NAME.to_owned(), "ready".into())))))).into()
+    }
+
+    fn set_state(context: &BTNodeExecutionContext,
+                 state: &str) {
+        let changed = ValuesPayload::singleton(VALUE_NAME.to_owned(), state.into());
+
+        context.put_values(&changed).unwrap();
+        context.handle_value_changes(&changed);
+    }
+
+}
@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use num::BigRational;
+use serde::{Deserialize, Serialize};
+
+use buttercup_conditions::arithmetic::ArithmeticExpression;
+
+use crate::context::BTNodeExecutionContext;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::composite::CompositeBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+///
+/// Scores all of its children and ticks the best one, returning its result.
+///
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct UtilitySelectorCompositeNode {
+
+    id: i32,
+    children: Vec<ScoredChild>,
+    hysteresis: Option<BigRational>,
+    tie_breaking: UtilityTieBreaking,
+    value_names: HashSet<String>
+
+}
+
+#[derive(Debug)]
+pub struct ScoredChild {
+
+    node: BTNode,
+    score: ArithmeticExpression
+
+}
+
+///
+/// Decides between the children of the same, best score.
+///
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum UtilityTieBreaking {
+
+    FirstChild,
+    PreviouslySelected
+
+}
+
+impl ScoredChild {
+
+    pub fn new(node: BTNode,
+               score: ArithmeticExpression) -> ScoredChild {
+        ScoredChild {
+            node,
+            score
+        }
+    }
+
+}
+
+impl UtilitySelectorCompositeNode {
+
+    ///
+    /// With hysteresis, the previously selected child is kept until another one outscores it
+    /// by more than the given margin.
+    ///
+    pub fn new(id: i32,
+               children: Vec<ScoredChild>,
+               hysteresis: Option<BigRational>,
+               tie_breaking: UtilityTieBreaking) -> UtilitySelectorCompositeNode {
+        let value_names = children
+            .iter()
+            .flat_map(|child| child.score.get_value_names())
+            .collect();
+
+        UtilitySelectorCompositeNode {
+            id,
+            children,
+            hysteresis,
+            tie_breaking,
+            value_names
+        }
+    }
+
+    fn get_scores(&self,
+                  context: &BTNodeExecutionContext) -> Result<Vec<BigRational>, TickError> {
+        let payload = context
+            .get_values(&self.value_names)
+            .map_err(|err| TickError::BlackboardError(self.id, err))?;
+
+        self.children
+            .iter()
+            .map(|child| child.score
+                .evaluate(&payload)
+                .map_err(|err| TickError::ArithmeticExpressionError(self.id, err)))
+            .collect()
+    }
+
+    fn select(&self,
+              scores: &[BigRational],
+              previous: Option<usize>) -> Option<usize> {
+        let best = scores.iter().max()?;
+
+        let previous = previous.filter(|index| *index < scores.len());
+
+        if let (Some(index), Some(hysteresis)) = (previous, &self.hysteresis) {
+            if &scores[index] + hysteresis >= *best {
+                return Some(index);
+            }
+        }
+
+        match (&self.tie_breaking, previous) {
+            (UtilityTieBreaking::PreviouslySelected, Some(index)) if scores[index] == *best =>
+                Some(index),
+            _ => scores.iter().position(|score| score == best)
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for UtilitySelectorCompositeNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let scores = self.get_scores(context)?;
+        let selection_context = context.get_selection_context();
+
+        match self.select(&scores, selection_context.get_selected_child(&self.id)) {
+            None => Result::Ok(TickStatus::Failure),
+            Some(index) => {
+                selection_context.set_selected_child(self.id, index);
+
+                self.children[index].node.tick(header, context).await
+            }
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<UtilitySelectorCompositeNode> for BTNode {
+    fn from(node: UtilitySelectorCompositeNode) -> Self {
+        BTNode::Composite(CompositeBTNode::UtilitySelector(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigInt;
+
+    use buttercup_values::{ValueHolder, ValuesPayload};
+
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_ticks_best_scored_child_breaking_ties_by_position() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let node = UtilitySelectorCompositeNode::new(
+                1,
+                vec![
+                    scored(2, "first"),
+                    scored(3, "second"),
+                    scored(4, "third")
+                ],
+                None,
+                UtilityTieBreaking::FirstChild);
+
+            put_scores(&context, &[1, 5, 5]);
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.tick(&TickHeader::default(), &context).await);
+            assert_eq!(Some(1), context.get_selection_context().get_selected_child(&1));
+
+            put_scores(&context, &[1, 5, 7]);
+
+            node.tick(&TickHeader::default(), &context).await.unwrap();
+            assert_eq!(Some(2), context.get_selection_context().get_selected_child(&1));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_keeps_previous_child_within_hysteresis() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let node = UtilitySelectorCompositeNode::new(
+                1,
+                vec![
+                    scored(2, "first"),
+                    scored(3, "second")
+                ],
+                Some(BigRational::from_integer(BigInt::from(2))),
+                UtilityTieBreaking::FirstChild);
+
+            put_scores(&context, &[5, 1]);
+            node.tick(&TickHeader::default(), &context).await.unwrap();
+            assert_eq!(Some(0), context.get_selection_context().get_selected_child(&1));
+
+            put_scores(&context, &[5, 7]);
+            node.tick(&TickHeader::default(), &context).await.unwrap();
+            assert_eq!(Some(0), context.get_selection_context().get_selected_child(&1));
+
+            put_scores(&context, &[5, 8]);
+            node.tick(&TickHeader::default(), &context).await.unwrap();
+            assert_eq!(Some(1), context.get_selection_context().get_selected_child(&1));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    fn scored(id: i32,
+              name: &str) -> ScoredChild {
+        ScoredChild::new(
+            PrintLogActionNode::new(id, name.to_owned()).into(),
+            ArithmeticExpression::Value(name.to_owned()))
+    }
+
+    fn put_scores(context: &BTNodeExecutionContext,
+                  scores: &[i64]) {
+        for (name, score) in ["first", "second", "third"].iter().zip(scores) {
+            context.put_values(
+                &ValuesPayload::singleton(
+                    (*name).to_owned(), ValueHolder::Integer(BigInt::from(*score)))).unwrap();
+        }
+    }
+
+}
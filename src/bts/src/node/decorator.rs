@@ -5,12 +5,16 @@ use async_trait::async_trait;
 use crate::context::BTNodeExecutionContext;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::decorator::condition::ConditionDecoratorNode;
+use crate::node::decorator::for_each::ForEachDecoratorNode;
 use crate::node::decorator::invert::InvertDecoratorNode;
+use crate::node::decorator::loops::{ForDecoratorNode, WhileDecoratorNode};
 use crate::node::decorator::reactive::ReactiveConditionDecoratorNode;
 use crate::tick::{TickError, TickHeader, TickStatus};
 
 pub mod condition;
+pub mod for_each;
 pub mod invert;
+pub mod loops;
 pub mod reactive;
 
 #[derive(Derivative)]
@@ -18,8 +22,11 @@ pub mod reactive;
 pub enum DecoratorBTNode {
 
     Condition(ConditionDecoratorNode),
+    For(ForDecoratorNode),
+    ForEach(ForEachDecoratorNode),
     Invert(InvertDecoratorNode),
-    ReactiveCondition(ReactiveConditionDecoratorNode)
+    ReactiveCondition(ReactiveConditionDecoratorNode),
+    While(WhileDecoratorNode)
 
 }
 
@@ -32,9 +39,15 @@ impl BehaviorTreeNode for DecoratorBTNode {
         match self {
             DecoratorBTNode::Condition(node) =>
                 node.do_tick(header, context).await,
+            DecoratorBTNode::For(node) =>
+                node.do_tick(header, context).await,
+            DecoratorBTNode::ForEach(node) =>
+                node.do_tick(header, context).await,
             DecoratorBTNode::Invert(node) =>
                 node.do_tick(header, context).await,
             DecoratorBTNode::ReactiveCondition(node) =>
+                node.do_tick(header, context).await,
+            DecoratorBTNode::While(node) =>
                 node.do_tick(header, context).await
         }
     }
@@ -42,8 +55,11 @@ impl BehaviorTreeNode for DecoratorBTNode {
     fn get_id(&self) -> &i32 {
         match self {
             DecoratorBTNode::Condition(node) => node.get_id(),
+            DecoratorBTNode::For(node) => node.get_id(),
+            DecoratorBTNode::ForEach(node) => node.get_id(),
             DecoratorBTNode::Invert(node) => node.get_id(),
             DecoratorBTNode::ReactiveCondition(node) => node.get_id(),
+            DecoratorBTNode::While(node) => node.get_id(),
         }
     }
 }
@@ -11,7 +11,7 @@ use buttercup_conditions::ConditionExpressionWrapper;
 use buttercup_values::ValuesPayload;
 
 use crate::context::BTNodeExecutionContext;
-use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::{BehaviorTreeNode, BTNode, check_condition, ConditionalNode};
 use crate::node::decorator::DecoratorBTNode;
 use crate::tick::{TickError, TickStatus, TickHeader};
 
@@ -67,6 +67,19 @@ impl BehaviorTreeNode for ConditionDecoratorNode {
     }
 }
 
+impl ConditionalNode for ConditionDecoratorNode {
+
+    fn get_condition_value_names(&self) -> HashSet<String> {
+        self.value_names.clone()
+    }
+
+    fn is_condition_met(&self,
+                        context: &BTNodeExecutionContext) -> Result<bool, TickError> {
+        check_condition(self.id, &self.value_names, self.predicate.deref(), context)
+    }
+
+}
+
 impl From<ConditionDecoratorNode> for BTNode {
     fn from(node: ConditionDecoratorNode) -> Self {
         BTNode::Decorator(DecoratorBTNode::Condition(node))
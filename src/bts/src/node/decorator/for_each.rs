@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use num::BigInt;
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::{ValueHolder, ValuesPayload};
+use buttercup_variables::{VariableName, VariableValueAccessError};
+
+use crate::context::BTNodeExecutionContext;
+use crate::node::{BehaviorTreeNode, BTNode};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+///
+/// Ticks the child once per element of a list, binding the element and its index
+/// to the given keys of the blackboard before each tick.
+///
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ForEachDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+    element_key: String,
+    failure_policy: ForEachFailurePolicy,
+    index_key: String,
+    list_name: VariableName
+
+}
+
+///
+/// With `Continue` all elements are visited and the node fails if any of the ticks failed.
+///
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum ForEachFailurePolicy {
+
+    BreakOnFailure,
+    Continue
+
+}
+
+impl ForEachDecoratorNode {
+
+    pub fn new(id: i32,
+               child: Box<BTNode>,
+               list_name: VariableName,
+               element_key: String,
+               index_key: String,
+               failure_policy: ForEachFailurePolicy) -> ForEachDecoratorNode {
+        ForEachDecoratorNode {
+            id,
+            child,
+            element_key,
+            failure_policy,
+            index_key,
+            list_name
+        }
+    }
+
+    fn get_elements(&self,
+                    context: &BTNodeExecutionContext) -> Result<Vec<ValueHolder>, TickError> {
+        match context.get_value(self.list_name.get_value()) {
+            Ok(Some(ValueHolder::List(list))) => Result::Ok(list.get_elements().clone()),
+            Ok(Some(_)) => Result::Err(
+                TickError::VariableValueAccessError(
+                    self.id, VariableValueAccessError::ValueHolderConversionError)),
+            Ok(None) => Result::Err(
+                TickError::VariableValueAccessError(
+                    self.id,
+                    VariableValueAccessError::VariableOfGivenNameNotFound(self.list_name.clone()))),
+            Err(err) => Result::Err(TickError::BlackboardError(self.id, err))
+        }
+    }
+
+    fn bind(&self,
+            context: &BTNodeExecutionContext,
+            index: usize,
+            element: ValueHolder) -> Result<(), TickError> {
+        let mut values = HashMap::new();
+
+        values.insert(self.element_key.clone(), element);
+        values.insert(self.index_key.clone(), ValueHolder::Integer(BigInt::from(index)));
+
+        context
+            .put_values(&ValuesPayload::new(values))
+            .map_err(|err| TickError::BlackboardError(self.id, err))
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for ForEachDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let mut status = TickStatus::Success;
+
+        for (index, element) in self.get_elements(context)?.into_iter().enumerate() {
+            self.bind(context, index, element)?;
+
+            if let TickStatus::Failure = self.child.tick(header, context).await? {
+                status = TickStatus::Failure;
+
+                if let ForEachFailurePolicy::BreakOnFailure = self.failure_policy {
+                    break;
+                }
+            }
+        }
+
+        Result::Ok(status)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<ForEachDecoratorNode> for BTNode {
+    fn from(node: ForEachDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::ForEach(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper, RelationalExpression, RelationalExpressionSpecification};
+    use buttercup_conditions::relational::NotEqualsRelationalExpression;
+    use buttercup_values::lists::ValueHoldersList;
+    use buttercup_values::ValueType;
+
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::decorator::condition::ConditionDecoratorNode;
+
+    use super::*;
+
+    const ELEMENT_KEY: &str = "recipient";
+    const INDEX_KEY: &str = "recipient_index";
+    const LIST_NAME: &str = "recipients";
+
+    #[actix_rt::test]
+    async fn test_breaks_on_first_failure() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+
+            put_recipients(&context);
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       for_each(ForEachFailurePolicy::BreakOnFailure)
+                           .tick(&TickHeader::default(), &context).await);
+            assert_eq!(Some("blocked".into()), context.get_value(&ELEMENT_KEY.to_owned()).unwrap());
+            assert_eq!(Some(ValueHolder::Integer(BigInt::from(1))),
+                       context.get_value(&INDEX_KEY.to_owned()).unwrap());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_continues_over_failures() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+
+            put_recipients(&context);
+
+            assert_eq!(Result::Ok(TickStatus::Failure),
+                       for_each(ForEachFailurePolicy::Continue)
+                           .tick(&TickHeader::default(), &context).await);
+            assert_eq!(Some("last".into()), context.get_value(&ELEMENT_KEY.to_owned()).unwrap());
+            assert_eq!(Some(ValueHolder::Integer(BigInt::from(2))),
+                       context.get_value(&INDEX_KEY.to_owned()).unwrap());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    fn for_each(failure_policy: ForEachFailurePolicy) -> ForEachDecoratorNode {
+        ForEachDecoratorNode::new(
+            1,
+            Box::new(
+                ConditionDecoratorNode::new(
+                    2,
+                    PrintLogActionNode::new(3, "Sending.".to_owned()).into(),
+                    ConditionExpressionWrapper::new(
+                        ConditionExpression::RelationExpression(
+                            RelationalExpression::NotEquals(
+                                NotEqualsRelationalExpression::new(
+                                    RelationalExpressionSpecification::NameAndLiteral(
+                                        ELEMENT_KEY.to_owned(), "blocked".into())))))).into()),
+            VariableName::new(LIST_NAME.to_owned()),
+            ELEMENT_KEY.to_owned(),
+            INDEX_KEY.to_owned(),
+            failure_policy)
+    }
+
+    fn put_recipients(context: &BTNodeExecutionContext) {
+        let recipients = ValueHoldersList::new(
+            vec!["first".into(), "blocked".into(), "last".into()], ValueType::String).unwrap();
+
+        context.put_values(
+            &ValuesPayload::singleton(
+                LIST_NAME.to_owned(), ValueHolder::List(Arc::new(recipients)))).unwrap();
+    }
+
+}
@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+use std::ops::Deref;
+
+use async_std::task;
+use async_trait::async_trait;
+use num::{BigInt, Signed, Zero};
+
+use buttercup_conditions::ConditionExpressionWrapper;
+use buttercup_values::{ValueHolder, ValuesPayload};
+use buttercup_variables::VariableSpecification;
+
+use crate::context::BTNodeExecutionContext;
+use crate::node::{BehaviorTreeNode, BTNode, check_condition};
+use crate::node::decorator::DecoratorBTNode;
+use crate::tick::{TickError, TickHeader, TickStatus};
+
+///
+/// Ticks the child as long as the condition is met, failing as soon as the child fails.
+/// The index is written before each check, so that the condition can refer to it.
+/// The loop yields after each iteration, so that a synchronous child cannot starve the runtime.
+///
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct WhileDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+    index_key: String,
+
+    #[derivative(Debug="ignore")]
+    predicate: Box<dyn Fn(&ValuesPayload) -> bool + Send + Sync>,
+
+    value_names: HashSet<String>
+
+}
+
+///
+/// Ticks the child for each index from the inclusive start to the exclusive end,
+/// failing as soon as the child fails.
+///
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct ForDecoratorNode {
+
+    id: i32,
+    child: Box<BTNode>,
+    index_key: String,
+
+    #[derivative(Debug="ignore")]
+    from: VariableSpecification<BigInt>,
+
+    #[derivative(Debug="ignore")]
+    to: VariableSpecification<BigInt>,
+
+    #[derivative(Debug="ignore")]
+    step: VariableSpecification<BigInt>
+
+}
+
+impl WhileDecoratorNode {
+
+    pub fn new(id: i32,
+               child: Box<BTNode>,
+               condition: ConditionExpressionWrapper,
+               index_key: String) -> WhileDecoratorNode {
+        let value_names = condition.get_value_names_cloned();
+        WhileDecoratorNode {
+            id,
+            child,
+            index_key,
+            predicate: condition.unpack(),
+            value_names
+        }
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for WhileDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let mut index = BigInt::zero();
+
+        loop {
+            put_index(self.id, &self.index_key, &index, context)?;
+
+            if !check_condition(self.id, &self.value_names, self.predicate.deref(), context)? {
+                return Result::Ok(TickStatus::Success);
+            }
+
+            if let TickStatus::Failure = self.child.tick(header, context).await? {
+                return Result::Ok(TickStatus::Failure);
+            }
+
+            index += 1;
+
+            task::yield_now().await;
+        }
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<WhileDecoratorNode> for BTNode {
+    fn from(node: WhileDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::While(node))
+    }
+}
+
+impl ForDecoratorNode {
+
+    pub fn new(id: i32,
+               child: Box<BTNode>,
+               from: VariableSpecification<BigInt>,
+               to: VariableSpecification<BigInt>,
+               step: VariableSpecification<BigInt>,
+               index_key: String) -> ForDecoratorNode {
+        ForDecoratorNode {
+            id,
+            child,
+            index_key,
+            from,
+            to,
+            step
+        }
+    }
+
+    fn get_value(&self,
+                 specification: &VariableSpecification<BigInt>,
+                 context: &BTNodeExecutionContext) -> Result<BigInt, TickError> {
+        specification
+            .get_value(context)
+            .map(|value| value.deref().clone())
+            .map_err(|err| TickError::VariableValueAccessError(self.id, err))
+    }
+
+}
+
+#[async_trait]
+impl BehaviorTreeNode for ForDecoratorNode {
+
+    async fn do_tick(&self,
+                     header: &TickHeader,
+                     context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let mut index = self.get_value(&self.from, context)?;
+        let to = self.get_value(&self.to, context)?;
+        let step = self.get_value(&self.step, context)?;
+
+        if step.is_zero() {
+            return Result::Err(TickError::InvalidLoopStep(self.id));
+        }
+
+        while (step.is_positive() && index < to) || (step.is_negative() && index > to) {
+            put_index(self.id, &self.index_key, &index, context)?;
+
+            if let TickStatus::Failure = self.child.tick(header, context).await? {
+                return Result::Ok(TickStatus::Failure);
+            }
+
+            index += &step;
+        }
+
+        Result::Ok(TickStatus::Success)
+    }
+
+    fn get_id(&self) -> &i32 {
+        &self.id
+    }
+}
+
+impl From<ForDecoratorNode> for BTNode {
+    fn from(node: ForDecoratorNode) -> Self {
+        BTNode::Decorator(DecoratorBTNode::For(node))
+    }
+}
+
+fn put_index(id: i32,
+             index_key: &str,
+             index: &BigInt,
+             context: &BTNodeExecutionContext) -> Result<(), TickError> {
+    context
+        .put_values(
+            &ValuesPayload::singleton(index_key.to_owned(), ValueHolder::Integer(index.clone())))
+        .map_err(|err| TickError::BlackboardError(id, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use buttercup_conditions::{ConditionExpression, RelationalExpression, RelationalExpressionSpecification};
+    use buttercup_conditions::relational::LessThanRelationalExpression;
+    use buttercup_variables::VariableName;
+    use futures::future::{Abortable, AbortHandle};
+
+    use crate::context::test_utils;
+    use crate::events::BTNodeExecutionEvent;
+    use crate::node::action::logging::PrintLogActionNode;
+
+    use super::*;
+
+    const INDEX_KEY: &str = "index";
+
+    #[actix_rt::test]
+    async fn test_while_ticks_child_until_condition_is_not_met() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let ticks = count_ticks(&context);
+            let node = WhileDecoratorNode::new(
+                1,
+                Box::new(PrintLogActionNode::new(2, "Looping.".to_owned()).into()),
+                ConditionExpressionWrapper::new(
+                    ConditionExpression::RelationExpression(
+                        RelationalExpression::LessThan(
+                            LessThanRelationalExpression::new(
+                                RelationalExpressionSpecification::NameAndLiteral(
+                                    INDEX_KEY.to_owned(), ValueHolder::Integer(BigInt::from(3))))))),
+                INDEX_KEY.to_owned());
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.tick(&TickHeader::default(), &context).await);
+            assert_eq!(3, ticks.load(Ordering::SeqCst));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_while_with_synchronous_child_can_be_stopped() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let node = WhileDecoratorNode::new(
+                1,
+                Box::new(PrintLogActionNode::new(2, "Looping.".to_owned()).into()),
+                ConditionExpressionWrapper::always_true(),
+                INDEX_KEY.to_owned());
+            let (abort_handle, abort_registration) = AbortHandle::new_pair();
+
+            let header = TickHeader::default();
+            let (result, _) = futures::join!(
+                Abortable::new(node.tick(&header, &context), abort_registration),
+                async {
+                    task::yield_now().await;
+                    abort_handle.abort();
+                });
+
+            assert!(result.is_err());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_for_counts_down_with_blackboard_bounds() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let ticks = count_ticks(&context);
+            let node = ForDecoratorNode::new(
+                1,
+                Box::new(PrintLogActionNode::new(2, "Looping.".to_owned()).into()),
+                VariableName::new("from".to_owned()).into(),
+                BigInt::from(0).into(),
+                BigInt::from(-2).into(),
+                INDEX_KEY.to_owned());
+
+            context.put_values(
+                &ValuesPayload::singleton(
+                    "from".to_owned(), ValueHolder::Integer(BigInt::from(5)))).unwrap();
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       node.tick(&TickHeader::default(), &context).await);
+            assert_eq!(3, ticks.load(Ordering::SeqCst));
+            assert_eq!(Some(ValueHolder::Integer(BigInt::from(1))),
+                       context.get_value(&INDEX_KEY.to_owned()).unwrap());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_for_rejects_zero_step() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let node = ForDecoratorNode::new(
+                1,
+                Box::new(PrintLogActionNode::new(2, "Looping.".to_owned()).into()),
+                BigInt::from(0).into(),
+                BigInt::from(1).into(),
+                BigInt::from(0).into(),
+                INDEX_KEY.to_owned());
+
+            assert_eq!(Result::Err(TickError::InvalidLoopStep(1)),
+                       node.tick(&TickHeader::default(), &context).await);
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    fn count_ticks(context: &BTNodeExecutionContext) -> Arc<AtomicUsize> {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let ticks_ref = ticks.clone();
+
+        context.get_event_sink().add_listener(Arc::new(move |event| {
+            if let BTNodeExecutionEvent::ExecutionStarted(started) = event {
+                if started.get_node_id() == &2 {
+                    ticks_ref.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }));
+
+        ticks
+    }
+
+}
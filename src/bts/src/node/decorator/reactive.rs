@@ -7,15 +7,16 @@ use std::sync::Arc;
 
 use actix_web::guard::Guard;
 use async_trait::async_trait;
-use futures::future::{Abortable, Aborted, AbortHandle, AbortRegistration};
+use futures::future::{Abortable, AbortHandle};
+use serde::{Deserialize, Serialize};
 
 use buttercup_blackboards::LocalBlackboardError;
 use buttercup_conditions::ConditionExpressionWrapper;
 use buttercup_values::ValuesPayload;
 
 use crate::context::BTNodeExecutionContext;
-use crate::context::reactive::ReactiveContextError;
-use crate::node::{BehaviorTreeNode, BTNode};
+use crate::context::reactive::{ReactiveActivationKey, ReactiveContextError};
+use crate::node::{BehaviorTreeNode, BTNode, check_condition, ConditionalNode};
 use crate::node::decorator::DecoratorBTNode;
 use crate::tick::{TickError, TickStatus, TickHeader};
 
@@ -34,14 +35,29 @@ pub enum DataChangeHandlingStatus {
 
 }
 
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
 pub enum DataChangeHandlingError {
 
     BlackboardError(i32, LocalBlackboardError),
+    PreemptionCheckError(i32, TickError),
     ReactiveServiceError(i32, ReactiveContextError),
     NonReactiveNodeCalledError
 
 }
 
+impl DataChangeHandlingError {
+
+    pub fn get_node_id(&self) -> Option<&i32> {
+        match self {
+            DataChangeHandlingError::BlackboardError(node_id, _) => Some(node_id),
+            DataChangeHandlingError::PreemptionCheckError(node_id, _) => Some(node_id),
+            DataChangeHandlingError::ReactiveServiceError(node_id, _) => Some(node_id),
+            DataChangeHandlingError::NonReactiveNodeCalledError => None
+        }
+    }
+
+}
+
 impl ReactiveConditionDecoratorNode {
 
     pub fn new(id: i32,
@@ -65,14 +81,29 @@ impl BehaviorTreeNode for ReactiveConditionDecoratorNode {
     async fn do_tick(&self,
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
-        match self.inner.register_abortable(&self.inner, context)? {
-            None => Result::Ok(TickStatus::Failure),
-            Some(abort_registration) =>
-                match Abortable::new(self.child.tick(header, context),
-                                     abort_registration).await {
-                    Ok(result) => result,
-                    Err(_) => Result::Ok(TickStatus::Failure)
-                },
+        if !self.is_condition_met(context)? {
+            return Result::Ok(TickStatus::Failure);
+        }
+
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let activation_key = ReactiveActivationKey::new(*self.inner.get_id());
+
+        let _registration = context.get_reactive_service().register(
+            abort_handle,
+            activation_key.clone(),
+            &self.inner);
+
+        let child_header = header.with_activation_key(activation_key.clone());
+        let mut child_tick = Abortable::new(
+            self.child.tick(&child_header, context), abort_registration);
+
+        match (&mut child_tick).await {
+            Ok(result) => result,
+            Err(_) => {
+                context.get_recording_context().preempt(&activation_key, child_tick);
+
+                Result::Ok(TickStatus::Failure)
+            }
         }
     }
 
@@ -81,6 +112,20 @@ impl BehaviorTreeNode for ReactiveConditionDecoratorNode {
     }
 }
 
+impl ConditionalNode for ReactiveConditionDecoratorNode {
+
+    fn get_condition_value_names(&self) -> HashSet<String> {
+        self.inner.value_names.clone()
+    }
+
+    fn is_condition_met(&self,
+                        context: &BTNodeExecutionContext) -> Result<bool, TickError> {
+        check_condition(
+            self.inner.id, &self.inner.value_names, self.inner.predicate.deref(), context)
+    }
+
+}
+
 impl From<ReactiveConditionDecoratorNode> for BTNode {
     fn from(node: ReactiveConditionDecoratorNode) -> Self {
         BTNode::Decorator(DecoratorBTNode::ReactiveCondition(node))
@@ -118,13 +163,31 @@ impl Eq for ReactiveConditionInnerNode {}
 
 impl ReactiveConditionInnerNode {
 
+    ///
+    /// Registers the activation of a composite guarding its running child. The composite
+    /// looks for preempting children itself, hence the node neither watches any values
+    /// nor aborts on its own.
+    ///
+    pub(crate) fn guarding(id: i32) -> ReactiveConditionInnerNode {
+        ReactiveConditionInnerNode {
+            id,
+            predicate: Box::new(|_| true),
+            value_names: HashSet::new()
+        }
+    }
+
+    ///
+    /// Only the values missing from the changed ones are read from the blackboard.
+    ///
     pub fn handle_value_change(&self,
-                               context: &BTNodeExecutionContext)
+                               context: &BTNodeExecutionContext,
+                               key: &ReactiveActivationKey,
+                               changed_values: &ValuesPayload)
                                -> Result<DataChangeHandlingStatus, DataChangeHandlingError> {
-        match context.get_values(&self.value_names) {
+        match self.get_values(context, changed_values) {
             Ok(payload) => {
                 if !self.predicate.deref()(&payload) {
-                    return match context.get_reactive_service().abort(&self.id) {
+                    return match context.get_reactive_service().abort(key) {
                         Ok(_) =>
                             Result::Ok(DataChangeHandlingStatus::AbortedExecution),
                         Err(err) =>
@@ -146,26 +209,174 @@ impl ReactiveConditionInnerNode {
         &self.value_names
     }
 
-    fn register_abortable(&self,
-                          inner: &Arc<ReactiveConditionInnerNode>,
-                          context: &BTNodeExecutionContext)
-                          -> Result<Option<AbortRegistration>, TickError> {
-        match context.get_values(&self.value_names) {
-            Ok(payload) => {
-                if self.predicate.deref()(&payload) {
-                    let (abort_handle, abort_registration) =
-                        AbortHandle::new_pair();
-                    return
-                        match context.get_reactive_service().register(abort_handle, inner) {
-                            Ok(_) =>
-                                Result::Ok(Option::Some(abort_registration)),
-                            Err(err) =>
-                                Result::Err(TickError::ReactiveServiceError(self.id, err))
-                        };
-                }
-                return Result::Ok(Option::None);
+    fn get_values(&self,
+                  context: &BTNodeExecutionContext,
+                  changed_values: &ValuesPayload) -> Result<ValuesPayload, LocalBlackboardError> {
+        let missing_value_names: HashSet<String> =
+            self.value_names.difference(changed_values.get_keys()).cloned().collect();
+
+        let mut values = context.get_values(&missing_value_names)?.into_values();
+
+        for value_name in &self.value_names {
+            if let Some(value) = changed_values.get(value_name) {
+                values.insert(value_name.clone(), value.clone());
             }
-            Err(err) => Result::Err(TickError::BlackboardError(self.id, err))
         }
+
+        Result::Ok(ValuesPayload::new(values))
+    }
+
+}
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::FutureExt;
+    use uuid::Uuid;
+
+    use buttercup_conditions::{ConditionExpression, RelationalExpression, RelationalExpressionSpecification};
+    use buttercup_conditions::relational::EqualsRelationalExpression;
+    use buttercup_values::ValueHolder;
+
+    use crate::context::clock::ManualClock;
+    use crate::context::test_utils;
+    use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::action::subtree::ExecuteSubTreeActionNode;
+    use crate::node::action::wait::WaitDurationActionNode;
+    use crate::node::composite::parallel::ParallelCompositeNode;
+    use crate::node::root::one_off::OneOffRootBTNode;
+    use crate::tree::BehaviorTree;
+
+    use super::*;
+
+    const VALUE_NAME: &str = "enabled";
+
+    #[actix_rt::test]
+    async fn test_aborts_each_activation_separately() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let node = enabled_when_true(
+                WaitDurationActionNode::new(2, Duration::from_secs(3600).into()).into());
+
+            set_enabled(&context, true);
+
+            let first_header = new_header();
+            let second_header = new_header();
+
+            let mut first_tick = node.tick(&first_header, &context).boxed();
+            let mut second_tick = node.tick(&second_header, &context).boxed();
+
+            assert!((&mut first_tick).now_or_never().is_none());
+            assert!((&mut second_tick).now_or_never().is_none());
+            assert_eq!(2, context.get_reactive_service().get_activations_count());
+
+            set_enabled(&context, false);
+
+            assert_eq!(Some(Result::Ok(TickStatus::Failure)), first_tick.now_or_never());
+            assert_eq!(Some(Result::Ok(TickStatus::Failure)), second_tick.now_or_never());
+            assert_eq!(0, context.get_reactive_service().get_activations_count());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_aborts_activations_of_subtree_shared_by_parallel_branches() {
+        let path = {
+            let context = test_utils::with_clock(Arc::new(ManualClock::default()));
+            let subtree = Arc::new(
+                BehaviorTree::new(
+                    10,
+                    OneOffRootBTNode::new(
+                        11,
+                        enabled_when_true(
+                            WaitDurationActionNode::new(
+                                12, Duration::from_secs(3600).into()).into()).into()).into()));
+            let node = ParallelCompositeNode::new(
+                1,
+                vec![
+                    ExecuteSubTreeActionNode::new(2, subtree.clone()).unwrap().into(),
+                    ExecuteSubTreeActionNode::new(3, subtree).unwrap().into()],
+                2).unwrap();
+
+            set_enabled(&context, true);
+
+            let header = new_header();
+            let mut tick = node.tick(&header, &context).boxed();
+
+            assert!((&mut tick).now_or_never().is_none());
+            assert_eq!(2, context.get_reactive_service().get_activations_count());
+
+            set_enabled(&context, false);
+
+            assert_eq!(Some(Result::Ok(TickStatus::Failure)), tick.now_or_never());
+            assert_eq!(0, context.get_reactive_service().get_activations_count());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_deregisters_when_execution_finishes_or_is_dropped() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+
+            set_enabled(&context, true);
+
+            let finishing = enabled_when_true(PrintLogActionNode::new(2, "Enabled.".to_owned()).into());
+
+            assert_eq!(Result::Ok(TickStatus::Success),
+                       finishing.tick(&new_header(), &context).await);
+            assert_eq!(0, context.get_reactive_service().get_activations_count());
+
+            let waiting = enabled_when_true(
+                WaitDurationActionNode::new(2, Duration::from_secs(3600).into()).into());
+
+            {
+                let header = new_header();
+                let mut tick = waiting.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, context.get_reactive_service().get_activations_count());
+            }
+
+            assert_eq!(0, context.get_reactive_service().get_activations_count());
+            assert_eq!(0, clock.get_sleepers_count());
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
     }
-}
\ No newline at end of file
+
+    fn enabled_when_true(child: BTNode) -> ReactiveConditionDecoratorNode {
+        ReactiveConditionDecoratorNode::new(
+            1,
+            child,
+            ConditionExpressionWrapper::new(
+                ConditionExpression::RelationExpression(
+                    RelationalExpression::Equals(
+                        EqualsRelationalExpression::new(
+                            RelationalExpressionSpecification::NameAndLiteral(
+                                VALUE_NAME.to_owned(), ValueHolder::Boolean(true)))))))
+    }
+
+    fn new_header() -> TickHeader {
+        TickHeader::new(Uuid::new_v4(), Uuid::new_v4(), 1, Uuid::new_v4())
+    }
+
+    fn set_enabled(context: &BTNodeExecutionContext,
+                   enabled: bool) {
+        let changed = ValuesPayload::singleton(VALUE_NAME.to_owned(), ValueHolder::Boolean(enabled));
+
+        context.put_values(&changed).unwrap();
+        context.handle_value_changes(&changed);
+    }
+
+}
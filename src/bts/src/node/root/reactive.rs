@@ -1,38 +1,93 @@
-use std::sync::Arc;
+use std::cmp::min;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::context::BTNodeExecutionContext;
 use crate::node::{BehaviorTreeNode, BTNode};
-use crate::node::decorator::DecoratorBTNode;
-use crate::node::decorator::reactive::ReactiveConditionDecoratorNode;
-use crate::node::root::RootBTNode;
 use crate::tick::{TickError, TickHeader, TickStatus};
 
 pub struct ReactiveRootBTNode {
 
     id: i32,
-    child: Box<ReactiveConditionDecoratorNode>,
+    child: Box<BTNode>,
+    restart_policy: ReactiveRootRestartPolicy,
     stop_on_error: bool
 
 }
 
+///
+/// Decides when the child is ticked again after it failed, or errored without stopping the root.
+///
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum ReactiveRootRestartPolicy {
+
+    RestartAfterBackoff(ExponentialBackoff),
+    RestartWhenConditionMet,
+    Stop
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub struct ExponentialBackoff {
+
+    initial: Duration,
+    max: Duration
+
+}
+
+impl ExponentialBackoff {
+
+    pub fn new(initial: Duration,
+               max: Duration) -> ExponentialBackoff {
+        ExponentialBackoff {
+            initial,
+            max
+        }
+    }
+
+    pub fn get_delay(&self,
+                     attempt: u32) -> Duration {
+        match 2u32.checked_pow(attempt).and_then(|factor| self.initial.checked_mul(factor)) {
+            Some(delay) => min(delay, self.max),
+            None => self.max
+        }
+    }
+
+}
+
 #[async_trait]
 impl BehaviorTreeNode for ReactiveRootBTNode {
 
     async fn do_tick(&self,
                      header: &TickHeader,
                      context: &BTNodeExecutionContext) -> Result<TickStatus, TickError> {
+        let mut attempt = 0;
+
         loop {
             let new_header = header.with_new_root_tick_id(Uuid::new_v4());
 
             let result = self.child.tick(&new_header, context).await;
 
-            if let Ok(TickStatus::Success) = result {
-                continue;
-            } else {
-                return result;
+            match result {
+                Ok(TickStatus::Success) => {
+                    attempt = 0;
+                    continue;
+                },
+                Err(_) if self.stop_on_error => return result,
+                _ => {}
+            }
+
+            match &self.restart_policy {
+                ReactiveRootRestartPolicy::RestartAfterBackoff(backoff) => {
+                    context.sleep(backoff.get_delay(attempt)).await;
+                    attempt = attempt.saturating_add(1);
+                },
+                ReactiveRootRestartPolicy::RestartWhenConditionMet =>
+                    self.wait_for_condition(context).await?,
+                ReactiveRootRestartPolicy::Stop => return result
             }
         }
     }
@@ -45,12 +100,200 @@ impl BehaviorTreeNode for ReactiveRootBTNode {
 impl ReactiveRootBTNode {
 
     pub fn new(id: i32,
-               child: Box<ReactiveConditionDecoratorNode>,
+               child: Box<BTNode>,
+               restart_policy: ReactiveRootRestartPolicy,
                stop_on_error: bool) -> ReactiveRootBTNode {
         ReactiveRootBTNode {
             id,
             child,
+            restart_policy,
             stop_on_error
         }
     }
+
+    ///
+    /// Children without a condition are restarted right away.
+    ///
+    async fn wait_for_condition(&self,
+                                context: &BTNodeExecutionContext) -> Result<(), TickError> {
+        let conditional = match self.child.as_conditional() {
+            Some(conditional) => conditional,
+            None => return Result::Ok(())
+        };
+
+        loop {
+            let changed =
+                context.get_reactive_service().watch(conditional.get_condition_value_names());
+
+            if conditional.is_condition_met(context)? {
+                return Result::Ok(());
+            }
+
+            changed.await;
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::FutureExt;
+
+    use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper, RelationalExpression, RelationalExpressionSpecification};
+    use buttercup_conditions::relational::EqualsRelationalExpression;
+    use buttercup_values::{ValueHolder, ValuesPayload};
+    use buttercup_variables::VariableName;
+
+    use crate::context::clock::ManualClock;
+    use crate::context::test_utils;
+    use crate::events::BTNodeExecutionEvent;
+    use crate::node::action::logging::PrintLogActionNode;
+    use crate::node::action::wait::WaitDurationActionNode;
+    use crate::node::composite::sequence::SequenceCompositeNode;
+    use crate::node::decorator::reactive::ReactiveConditionDecoratorNode;
+
+    use super::*;
+
+    const VALUE_NAME: &str = "enabled";
+
+    #[actix_rt::test]
+    async fn test_stops_on_error_when_configured() {
+        let path = {
+            let context = BTNodeExecutionContext::default();
+            let node = ReactiveRootBTNode::new(
+                1,
+                Box::new(enabled_when_true(
+                    WaitDurationActionNode::new(
+                        3, VariableName::new("missing".to_owned()).into()).into())),
+                ReactiveRootRestartPolicy::RestartWhenConditionMet,
+                true);
+
+            context.put_values(
+                &ValuesPayload::singleton(VALUE_NAME.to_owned(), ValueHolder::Boolean(true))).unwrap();
+
+            assert!(matches!(node.tick(&TickHeader::default(), &context).await,
+                             Err(TickError::VariableValueAccessError(3, _))));
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_restarts_after_backoff() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let ticks = count_ticks(&context, 2);
+            let node = ReactiveRootBTNode::new(
+                1,
+                Box::new(enabled_when_true(PrintLogActionNode::new(3, "Ticked.".to_owned()).into())),
+                ReactiveRootRestartPolicy::RestartAfterBackoff(
+                    ExponentialBackoff::new(Duration::from_secs(1), Duration::from_secs(2))),
+                false);
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, ticks.load(Ordering::SeqCst));
+
+                clock.advance(Duration::from_secs(1));
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(2, ticks.load(Ordering::SeqCst));
+
+                clock.advance(Duration::from_secs(1));
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(2, ticks.load(Ordering::SeqCst));
+
+                clock.advance(Duration::from_secs(1));
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(3, ticks.load(Ordering::SeqCst));
+            }
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    #[actix_rt::test]
+    async fn test_waits_until_condition_is_met() {
+        let path = {
+            let clock = Arc::new(ManualClock::default());
+            let context = test_utils::with_clock(clock.clone());
+            let ticks = count_ticks(&context, 2);
+            let node = ReactiveRootBTNode::new(
+                1,
+                Box::new(
+                    enabled_when_true(
+                        SequenceCompositeNode::new(
+                            3,
+                            vec![
+                                PrintLogActionNode::new(4, "Enabled.".to_owned()).into(),
+                                WaitDurationActionNode::new(
+                                    5, Duration::from_secs(3600).into()).into()
+                            ]).into())),
+                ReactiveRootRestartPolicy::RestartWhenConditionMet,
+                false);
+
+            {
+                let header = TickHeader::default();
+                let mut tick = node.tick(&header, &context).boxed();
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(1, ticks.load(Ordering::SeqCst));
+                assert_eq!(1, context.get_reactive_service().get_watchers_count());
+                assert_eq!(0, clock.get_sleepers_count());
+
+                let changed =
+                    ValuesPayload::singleton(VALUE_NAME.to_owned(), ValueHolder::Boolean(true));
+
+                context.put_values(&changed).unwrap();
+                context.handle_value_changes(&changed);
+
+                assert!((&mut tick).now_or_never().is_none());
+                assert_eq!(2, ticks.load(Ordering::SeqCst));
+                assert_eq!(1, clock.get_sleepers_count());
+            }
+
+            test_utils::get_path(&context)
+        };
+
+        test_utils::destroy(path);
+    }
+
+    fn enabled_when_true(child: BTNode) -> BTNode {
+        ReactiveConditionDecoratorNode::new(
+            2,
+            child,
+            ConditionExpressionWrapper::new(
+                ConditionExpression::RelationExpression(
+                    RelationalExpression::Equals(
+                        EqualsRelationalExpression::new(
+                            RelationalExpressionSpecification::NameAndLiteral(
+                                VALUE_NAME.to_owned(), ValueHolder::Boolean(true))))))).into()
+    }
+
+    fn count_ticks(context: &BTNodeExecutionContext,
+                   node_id: i32) -> Arc<AtomicUsize> {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let ticks_ref = ticks.clone();
+
+        context.get_event_sink().add_listener(Arc::new(move |event| {
+            if let BTNodeExecutionEvent::ExecutionStarted(started) = event {
+                if started.get_node_id() == &node_id {
+                    ticks_ref.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }));
+
+        ticks
+    }
+
 }
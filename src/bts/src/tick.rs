@@ -8,9 +8,11 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use buttercup_blackboards::LocalBlackboardError;
+use buttercup_conditions::arithmetic::ArithmeticExpressionError;
 use buttercup_variables::VariableValueAccessError;
 
-use crate::context::reactive::ReactiveContextError;
+use crate::context::reactive::{ReactiveActivationKey, ReactiveContextError};
+use crate::context::recording::ReplayDivergence;
 
 #[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
 pub enum TickStatus {
@@ -24,9 +26,12 @@ pub enum TickStatus {
 pub enum TickError {
 
     AbortedExecution(i32),
+    ArithmeticExpressionError(i32, ArithmeticExpressionError),
     BlackboardError(i32, LocalBlackboardError),
     CompositeError(i32, Arc<Vec<(i32, TickError)>>),
+    InvalidLoopStep(i32),
     ReactiveServiceError(i32, ReactiveContextError),
+    ReplayDiverged(i32, ReplayDivergence),
     VariableValueAccessError(i32, VariableValueAccessError)
 
 }
@@ -36,9 +41,12 @@ impl TickError {
     pub fn get_node_id(&self) -> &i32 {
         match self {
             TickError::AbortedExecution(id) => id,
+            TickError::ArithmeticExpressionError(id, _) => id,
             TickError::BlackboardError(id, _) => id,
             TickError::CompositeError(id, _) => id,
+            TickError::InvalidLoopStep(id) => id,
             TickError::ReactiveServiceError(id, _) => id,
+            TickError::ReplayDiverged(id, _) => id,
             TickError::VariableValueAccessError(id, _) => id
         }
     }
@@ -53,7 +61,9 @@ pub struct TickHeader {
     root_tick_id: Uuid,
 
     tree_id: i32,
-    tree_tick_id: Uuid
+    tree_tick_id: Uuid,
+
+    activation_keys: Vec<ReactiveActivationKey>
 
 }
 
@@ -67,7 +77,8 @@ impl TickHeader {
             correlation_id,
             root_tick_id,
             tree_id,
-            tree_tick_id
+            tree_tick_id,
+            activation_keys: Vec::new()
         }
     }
 
@@ -87,9 +98,34 @@ impl TickHeader {
         &self.tree_tick_id
     }
 
+    ///
+    /// Keys of the enclosing activations which may preempt the execution.
+    ///
+    pub fn get_activation_keys(&self) -> &Vec<ReactiveActivationKey> {
+        &self.activation_keys
+    }
+
     pub fn with_new_root_tick_id(&self,
                                  new_root_tick_id: Uuid) -> TickHeader {
-        TickHeader::new(self.correlation_id, new_root_tick_id, self.tree_id, self.tree_tick_id)
+        TickHeader {
+            activation_keys: self.activation_keys.clone(),
+            ..TickHeader::new(self.correlation_id, new_root_tick_id, self.tree_id, self.tree_tick_id)
+        }
+    }
+
+    ///
+    /// Header of the children of a preemptible activation, so that the actions dropped
+    /// by preempting them are told apart from the ones interrupted from outside.
+    ///
+    pub fn with_activation_key(&self,
+                               activation_key: ReactiveActivationKey) -> TickHeader {
+        let mut activation_keys = self.activation_keys.clone();
+        activation_keys.push(activation_key);
+
+        TickHeader {
+            activation_keys,
+            ..TickHeader::new(self.correlation_id, self.root_tick_id, self.tree_id, self.tree_tick_id)
+        }
     }
 
 }
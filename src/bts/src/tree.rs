@@ -1,9 +1,12 @@
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use buttercup_endpoints::{ArgumentsExtractor, ArgumentSetDefinition};
+
 use crate::context::BTNodeExecutionContext;
 use crate::node::{BehaviorTreeNode, BTNode};
 use crate::node::root::RootBTNode;
@@ -12,7 +15,10 @@ use crate::tick::{TickError, TickHeader, TickStatus};
 pub struct BehaviorTree {
 
     id: i32,
-    root: RootBTNode
+    arguments: ArgumentsExtractor,
+    endpoints: HashMap<String, ArgumentSetDefinition>,
+    root: RootBTNode,
+    version: u32
 
 }
 
@@ -20,9 +26,26 @@ impl BehaviorTree {
 
     pub fn new(id: i32,
                root: RootBTNode) -> BehaviorTree {
+        BehaviorTree::with_arguments(id, ArgumentsExtractor::default(), Vec::new(), root)
+    }
+
+    ///
+    /// Arguments are the typed parameters the agents of the tree are created with,
+    /// endpoints are the argument sets accepted by its agents, by the name of the set.
+    ///
+    pub fn with_arguments(id: i32,
+                          arguments: ArgumentsExtractor,
+                          endpoints: Vec<ArgumentSetDefinition>,
+                          root: RootBTNode) -> BehaviorTree {
         BehaviorTree {
             id,
-            root
+            arguments,
+            endpoints: endpoints
+                .into_iter()
+                .map(|endpoint| (endpoint.get_name().clone(), endpoint))
+                .collect(),
+            root,
+            version: 0
         }
     }
 
@@ -45,6 +68,21 @@ impl BehaviorTree {
         &self.id
     }
 
+    ///
+    /// Version assigned once the tree is inserted into the tree service, zero before.
+    ///
+    pub fn get_version(&self) -> &u32 {
+        &self.version
+    }
+
+    pub fn get_arguments(&self) -> &ArgumentsExtractor {
+        &self.arguments
+    }
+
+    pub fn get_endpoints(&self) -> &HashMap<String, ArgumentSetDefinition> {
+        &self.endpoints
+    }
+
     pub fn can_be_subtree(&self) -> bool {
         self.root.can_be_subtree_root()
     }
@@ -58,31 +96,137 @@ pub struct BehaviorTreeDefinition {
 
 }
 
+///
+/// Keeps every inserted version of each tree, versions are never overwritten nor reused,
+/// not even once the tree is removed. Pinned versions, e.g. executed by agents, cannot be removed.
+///
 #[derive(Default)]
 pub struct BehaviorTreeService {
 
-    trees: DashMap<i32, Arc<BehaviorTree>>,
+    latest_versions: DashMap<i32, u32>,
+    pins: DashMap<(i32, u32), usize>,
+    trees: DashMap<i32, BTreeMap<u32, Arc<BehaviorTree>>>
+
+}
+
+#[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Debug, Clone)]
+pub enum BehaviorTreeServiceError {
+
+    TreeVersionIsPinned(i32, u32)
 
 }
 
 impl BehaviorTreeService {
 
+    ///
+    /// Inserts the tree as the next version of the trees of its id, starting with one.
+    ///
     pub fn insert(&self,
-                  tree: BehaviorTree) {
-        self.insert_arc(Arc::new(tree));
+                  mut tree: BehaviorTree) -> Arc<BehaviorTree> {
+        tree.version = {
+            let mut latest_version = self.latest_versions.entry(tree.id).or_insert(0);
+            *latest_version += 1;
+            *latest_version
+        };
+
+        let tree = Arc::new(tree);
+
+        self.trees.entry(tree.id).or_default().insert(tree.version, tree.clone());
+
+        tree
+    }
+
+    ///
+    /// Inserts the tree under the given version, e.g. when restoring persisted trees.
+    /// Later insertions continue after the greatest version known for the tree.
+    ///
+    pub fn insert_version(&self,
+                          mut tree: BehaviorTree,
+                          version: u32) -> Arc<BehaviorTree> {
+        tree.version = version;
+
+        self.reserve_versions(&tree.id, version);
+
+        let tree = Arc::new(tree);
+
+        self.trees.entry(tree.id).or_default().insert(tree.version, tree.clone());
+
+        tree
     }
 
-    pub fn insert_arc(&self, tree: Arc<BehaviorTree>) {
-        self.trees.insert(tree.id, tree);
+    ///
+    /// Makes sure versions up to the given one are not assigned to the tree anymore.
+    ///
+    pub fn reserve_versions(&self,
+                            id: &i32,
+                            latest_version: u32) {
+        let mut reserved_version = self.latest_versions.entry(*id).or_insert(0);
+
+        if *reserved_version < latest_version {
+            *reserved_version = latest_version;
+        }
     }
 
+    ///
+    /// Returns the latest version of the tree.
+    ///
     pub fn get_by_id(&self,
                      id: &i32) -> Option<Arc<BehaviorTree>> {
-        self.trees.get(id).map(|tree_arc| tree_arc.clone())
+        self.trees
+            .get(id)
+            .and_then(|versions| versions.values().next_back().cloned())
     }
 
-}
+    pub fn get_version(&self,
+                       id: &i32,
+                       version: &u32) -> Option<Arc<BehaviorTree>> {
+        self.trees
+            .get(id)
+            .and_then(|versions| versions.get(version).cloned())
+    }
+
+    pub fn get_versions(&self,
+                        id: &i32) -> Vec<u32> {
+        self.trees
+            .get(id)
+            .map(|versions| versions.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn pin(&self,
+               id: &i32,
+               version: &u32) {
+        *self.pins.entry((*id, *version)).or_insert(0) += 1;
+    }
+
+    pub fn unpin(&self,
+                 id: &i32,
+                 version: &u32) {
+        let key = (*id, *version);
+
+        if let Some(mut pins) = self.pins.get_mut(&key) {
+            *pins = pins.saturating_sub(1);
+        }
+
+        self.pins.remove_if(&key, |_, pins| *pins == 0);
+    }
+
+    ///
+    /// Removes all versions of the tree, unless any of them is pinned. Returns false
+    /// if there were none.
+    ///
+    pub fn remove(&self,
+                  id: &i32) -> Result<bool, BehaviorTreeServiceError> {
+        if let Some(version) = self.get_versions(id)
+            .into_iter()
+            .find(|version| self.pins.contains_key(&(*id, *version))) {
+            return Result::Err(BehaviorTreeServiceError::TreeVersionIsPinned(*id, version));
+        }
+
+        Result::Ok(self.trees.remove(id).is_some())
+    }
 
+}
 
 #[cfg(test)]
 mod tests {
@@ -118,4 +262,44 @@ mod tests {
         test_utils::destroy(path);
     }
 
+    #[test]
+    fn test_keeps_all_versions_of_tree() {
+        let service = BehaviorTreeService::default();
+        let build_tree = |message: &str|
+            BehaviorTree::new(
+                1,
+                OneOffRootBTNode::new(
+                    1, PrintLogActionNode::new(2, message.to_owned()).into()).into());
+
+        assert_eq!(1, *service.insert(build_tree("first")).get_version());
+        assert_eq!(2, *service.insert(build_tree("second")).get_version());
+        assert_eq!(Some(2), service.get_by_id(&1).map(|tree| *tree.get_version()));
+        assert_eq!(Some(1), service.get_version(&1, &1).map(|tree| *tree.get_version()));
+        assert!(service.get_version(&1, &3).is_none());
+        assert_eq!(vec![1, 2], service.get_versions(&1));
+        assert_eq!(Ok(true), service.remove(&1));
+        assert!(service.get_versions(&1).is_empty());
+        assert_eq!(3, *service.insert(build_tree("third")).get_version());
+    }
+
+    #[test]
+    fn test_keeps_pinned_versions_of_tree() {
+        let service = BehaviorTreeService::default();
+
+        service.insert(
+            BehaviorTree::new(
+                1,
+                OneOffRootBTNode::new(
+                    1, PrintLogActionNode::new(2, "Pinned.".to_owned()).into()).into()));
+        service.pin(&1, &1);
+        service.pin(&1, &1);
+        service.unpin(&1, &1);
+
+        assert_eq!(Err(BehaviorTreeServiceError::TreeVersionIsPinned(1, 1)), service.remove(&1));
+
+        service.unpin(&1, &1);
+
+        assert_eq!(Ok(true), service.remove(&1));
+    }
+
 }
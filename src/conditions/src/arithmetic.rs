@@ -0,0 +1,143 @@
+use num::{BigInt, BigRational, Zero};
+use serde::{Deserialize, Serialize};
+
+use buttercup_values::{ValueHolder, ValuesPayload};
+
+///
+/// Numeric expression over the values, operating on `Integer` and `Decimal` values.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+pub enum ArithmeticExpression {
+
+    Add(Vec<ArithmeticExpression>),
+    Divide(Box<ArithmeticExpression>, Box<ArithmeticExpression>),
+    Literal(ValueHolder),
+    Multiply(Vec<ArithmeticExpression>),
+    Negate(Box<ArithmeticExpression>),
+    Subtract(Box<ArithmeticExpression>, Box<ArithmeticExpression>),
+    Value(String)
+
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, Eq, PartialEq, PartialOrd)]
+pub enum ArithmeticExpressionError {
+
+    DivisionByZero,
+    NonNumericLiteral(String),
+    NonNumericValue(String),
+    ValueNotFound(String)
+
+}
+
+impl ArithmeticExpression {
+
+    pub fn evaluate(&self,
+                    payload: &ValuesPayload) -> Result<BigRational, ArithmeticExpressionError> {
+        match self {
+            ArithmeticExpression::Add(expressions) => {
+                let mut sum = BigRational::zero();
+                for expr in expressions {
+                    sum += expr.evaluate(payload)?;
+                }
+                Result::Ok(sum)
+            },
+            ArithmeticExpression::Divide(dividend, divisor) => {
+                let divisor = divisor.evaluate(payload)?;
+                if divisor.is_zero() {
+                    return Result::Err(ArithmeticExpressionError::DivisionByZero);
+                }
+                Result::Ok(dividend.evaluate(payload)? / divisor)
+            },
+            ArithmeticExpression::Literal(value) =>
+                to_rational(value)
+                    .ok_or_else(|| ArithmeticExpressionError::NonNumericLiteral(
+                        value.as_ref().to_owned())),
+            ArithmeticExpression::Multiply(expressions) => {
+                let mut product = BigRational::from_integer(BigInt::from(1));
+                for expr in expressions {
+                    product *= expr.evaluate(payload)?;
+                }
+                Result::Ok(product)
+            },
+            ArithmeticExpression::Negate(expr) => Result::Ok(-expr.evaluate(payload)?),
+            ArithmeticExpression::Subtract(minuend, subtrahend) =>
+                Result::Ok(minuend.evaluate(payload)? - subtrahend.evaluate(payload)?),
+            ArithmeticExpression::Value(name) =>
+                match payload.get(name) {
+                    None => Result::Err(ArithmeticExpressionError::ValueNotFound(name.clone())),
+                    Some(value) => to_rational(value)
+                        .ok_or_else(|| ArithmeticExpressionError::NonNumericValue(name.clone()))
+                }
+        }
+    }
+
+    pub fn get_value_names(&self) -> Vec<String> {
+        match self {
+            ArithmeticExpression::Add(expressions) | ArithmeticExpression::Multiply(expressions) =>
+                expressions.iter().flat_map(ArithmeticExpression::get_value_names).collect(),
+            ArithmeticExpression::Divide(first, second)
+            | ArithmeticExpression::Subtract(first, second) => {
+                let mut value_names = first.get_value_names();
+                value_names.extend(second.get_value_names());
+                value_names
+            },
+            ArithmeticExpression::Literal(_) => Vec::new(),
+            ArithmeticExpression::Negate(expr) => expr.get_value_names(),
+            ArithmeticExpression::Value(name) => vec![name.clone()]
+        }
+    }
+
+}
+
+fn to_rational(value: &ValueHolder) -> Option<BigRational> {
+    match value {
+        ValueHolder::Decimal(decimal) => Some(decimal.clone()),
+        ValueHolder::Integer(integer) => Some(BigRational::from_integer(integer.clone())),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use num::FromPrimitive;
+
+    use super::*;
+
+    #[test]
+    fn test_evaluates_mixed_integers_and_decimals() {
+        let mut values = HashMap::new();
+        values.insert("hits".to_owned(), ValueHolder::Integer(BigInt::from(3)));
+        values.insert("weight".to_owned(), ValueHolder::Decimal(BigRational::from_f64(0.5).unwrap()));
+        let payload = ValuesPayload::new(values);
+
+        let expression = ArithmeticExpression::Subtract(
+            Box::new(
+                ArithmeticExpression::Multiply(
+                    vec![
+                        ArithmeticExpression::Value("hits".to_owned()),
+                        ArithmeticExpression::Value("weight".to_owned())
+                    ])),
+            Box::new(ArithmeticExpression::Literal(ValueHolder::Integer(BigInt::from(1)))));
+
+        assert_eq!(Ok(BigRational::from_f64(0.5).unwrap()), expression.evaluate(&payload));
+        assert_eq!(vec!["hits".to_owned(), "weight".to_owned()], expression.get_value_names());
+    }
+
+    #[test]
+    fn test_fails_on_division_by_zero_and_missing_values() {
+        let payload = ValuesPayload::singleton(
+            "zero".to_owned(), ValueHolder::Integer(BigInt::from(0)));
+
+        assert_eq!(
+            Err(ArithmeticExpressionError::DivisionByZero),
+            ArithmeticExpression::Divide(
+                Box::new(ArithmeticExpression::Literal(ValueHolder::Integer(BigInt::from(1)))),
+                Box::new(ArithmeticExpression::Value("zero".to_owned()))).evaluate(&payload));
+        assert_eq!(
+            Err(ArithmeticExpressionError::ValueNotFound("missing".to_owned())),
+            ArithmeticExpression::Value("missing".to_owned()).evaluate(&payload));
+    }
+
+}
@@ -8,6 +8,7 @@ use buttercup_values::{ValueHolder, ValuesPayload, ValueType};
 
 use crate::relational::{ContainsRelationalExpression, EndsWithRelationalExpression, EqualsRelationalExpression, GreaterThanOrEqualsRelationalExpression, GreaterThanRelationalExpression, IsInRelationalExpression, LessThanOrEqualsRelationalExpression, LessThanRelationalExpression, NotEqualsRelationalExpression, StartsWithRelationalExpression};
 
+pub mod arithmetic;
 pub mod relational;
 
 use serde::{Deserialize, Serialize};
@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use std::sync::Arc;
 
 use actix::Arbiter;
@@ -7,15 +6,15 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use buttercup_blackboards::{LocalBlackboard, LocalBlackboardError, LocalBlackboardService};
-use buttercup_values::ValuesPayload;
+use buttercup_values::{ValueHolder, ValuesPayload};
 
-type Listener = Arc<dyn Fn(&HashSet<String>) + Send + Sync>;
+pub type Listener = Arc<dyn Fn(&ValuesPayload) + Send + Sync>;
 
 pub struct EndpointService {
 
     arbiter: Arbiter,
     blackboard_service: Arc<LocalBlackboardService>,
-    listeners: DashMap<Uuid, Listener>
+    listeners_by_blackboard_ids: DashMap<Uuid, DashMap<Uuid, Listener>>
 
 }
 
@@ -47,7 +46,7 @@ impl EndpointService {
         EndpointService {
             arbiter,
             blackboard_service,
-            listeners: DashMap::new()
+            listeners_by_blackboard_ids: DashMap::new()
         }
     }
 
@@ -58,29 +57,182 @@ impl EndpointService {
             .get(blackboard_id)?
             .put_values(&payload)?;
 
-        let keys = payload.into_keys();
+        self.notify_listeners(blackboard_id, payload);
 
+        Result::Ok(())
+    }
+
+    ///
+    /// Removes all the values missing from the payload, listeners are notified about
+    /// the removed names as well.
+    ///
+    pub fn replace_values(&self,
+                          blackboard_id: &Uuid,
+                          payload: ValuesPayload) -> Result<(), EndpointError> {
+        let removed_names = self.blackboard_service
+            .get(blackboard_id)?
+            .replace_values(&payload)?;
 
-        let listeners: Vec<Listener> = {
-            self.listeners
+        self.notify_listeners(
+            blackboard_id, ValuesPayload::with_removed(payload.into_values(), removed_names));
+
+        Result::Ok(())
+    }
+
+    pub fn get_values(&self,
+                      blackboard_id: &Uuid) -> Result<ValuesPayload, EndpointError> {
+        Result::Ok(self.blackboard_service.get(blackboard_id)?.get_all_values()?)
+    }
+
+    pub fn get_value(&self,
+                     blackboard_id: &Uuid,
+                     value_name: &String) -> Result<Option<ValueHolder>, EndpointError> {
+        Result::Ok(self.blackboard_service.get(blackboard_id)?.get_value(value_name)?)
+    }
+
+    fn notify_listeners(&self,
+                        blackboard_id: &Uuid,
+                        payload: ValuesPayload) {
+        if let Some(listeners) = self.listeners_by_blackboard_ids.get(blackboard_id) {
+            let listeners: Vec<Listener> = listeners
+                .value()
                 .iter()
                 .map(|entry| entry.value().clone())
-                .collect()
+                .collect();
+
+            self.arbiter.spawn_fn(move || {
+                for listener in listeners {
+                    listener(&payload);
+                }
+            });
+        }
+    }
+
+    ///
+    /// Listener is notified only about the changes of the given blackboard, with changed values.
+    ///
+    pub fn add_listener(&self,
+                        blackboard_id: Uuid,
+                        listener: Listener) -> Uuid {
+        let listener_id = Uuid::new_v4();
+
+        self.listeners_by_blackboard_ids
+            .entry(blackboard_id)
+            .or_insert_with(DashMap::new)
+            .insert(listener_id, listener);
+
+        listener_id
+    }
+
+    pub fn remove_listener(&self,
+                           blackboard_id: &Uuid,
+                           listener_id: &Uuid) -> bool {
+        let removed = match self.listeners_by_blackboard_ids.get(blackboard_id) {
+            Some(listeners) => listeners.value().remove(listener_id).is_some(),
+            None => false
         };
 
-        self.arbiter.spawn_fn(move || {
-            for listener in listeners {
-                listener(&keys);
-            }
-        });
+        self.listeners_by_blackboard_ids
+            .remove_if(blackboard_id, |_, listeners| listeners.is_empty());
 
-        Result::Ok(())
+        removed
     }
 
-    pub fn add_listener(&self,
-                        listener: Arc<dyn Fn(&HashSet<String>) + Send + Sync>) {
-        self.listeners.insert(Uuid::new_v4(), listener);
+    pub fn get_listeners_count(&self) -> usize {
+        self.listeners_by_blackboard_ids
+            .iter()
+            .map(|entry| entry.value().len())
+            .sum()
     }
 
 }
 
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::iter::FromIterator;
+    use std::sync::{mpsc, Mutex};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_notifies_only_listener_of_changed_blackboard() {
+        let blackboard_service = Arc::new(LocalBlackboardService::default());
+        let service = EndpointService::new(Arbiter::new(), blackboard_service.clone());
+        let (sender, receiver) = mpsc::channel();
+
+        let blackboard_ids = vec![Uuid::new_v4(), Uuid::new_v4()];
+
+        for blackboard_id in &blackboard_ids {
+            blackboard_service.create(blackboard_id, format!("{}.bb", blackboard_id).into())
+                .unwrap();
+
+            let sender = Mutex::new(sender.clone());
+            let blackboard_id = *blackboard_id;
+            service.add_listener(blackboard_id, Arc::new(move |payload| {
+                sender.lock().unwrap().send((blackboard_id, payload.clone())).unwrap();
+            }));
+        }
+
+        let payload = ValuesPayload::singleton("name".to_owned(), "value".into());
+
+        service.accept_value_changes(&blackboard_ids[0], payload.clone()).unwrap();
+
+        assert_eq!((blackboard_ids[0], payload),
+                   receiver.recv_timeout(Duration::from_secs(1)).unwrap());
+        assert!(receiver.recv_timeout(Duration::from_millis(50)).is_err());
+
+        for blackboard_id in &blackboard_ids {
+            blackboard_service.destroy(blackboard_id).unwrap();
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_notifies_listener_about_removed_values() {
+        let blackboard_service = Arc::new(LocalBlackboardService::default());
+        let service = EndpointService::new(Arbiter::new(), blackboard_service.clone());
+        let (sender, receiver) = mpsc::channel();
+
+        let blackboard_id = Uuid::new_v4();
+
+        blackboard_service.create(&blackboard_id, format!("{}.bb", blackboard_id).into())
+            .unwrap();
+        service.accept_value_changes(
+            &blackboard_id, ValuesPayload::singleton("removed".to_owned(), "value".into()))
+            .unwrap();
+
+        let sender = Mutex::new(sender);
+        service.add_listener(blackboard_id, Arc::new(move |payload| {
+            sender.lock().unwrap().send(payload.clone()).unwrap();
+        }));
+
+        service.replace_values(
+            &blackboard_id, ValuesPayload::singleton("kept".to_owned(), "value".into()))
+            .unwrap();
+
+        let payload = receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+
+        assert_eq!(HashSet::from_iter(vec!["kept".to_owned(), "removed".to_owned()]),
+                   *payload.get_keys());
+        assert_eq!(None, payload.get(&"removed".to_owned()));
+        assert_eq!(1, service.get_values(&blackboard_id).unwrap().get_values().len());
+
+        blackboard_service.destroy(&blackboard_id).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn test_removes_listener() {
+        let service = EndpointService::default();
+        let blackboard_id = Uuid::new_v4();
+
+        let listener_id = service.add_listener(blackboard_id, Arc::new(|_| {}));
+
+        assert_eq!(1, service.get_listeners_count());
+        assert!(service.remove_listener(&blackboard_id, &listener_id));
+        assert!(!service.remove_listener(&blackboard_id, &listener_id));
+        assert_eq!(0, service.get_listeners_count());
+    }
+
+}
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
-use buttercup_values::{ValueHolder, ValuesPayload};
-use buttercup_values::extractors::{ValueExtractionError, ValueExtractorInput, ValueExtractorService};
+use buttercup_values::{ValueHolder, ValuesPayload, ValueType};
+use buttercup_values::extractors::{ValueExtractionError, ValueExtractionPolicy, ValueExtractorInput, ValueExtractorService};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
@@ -26,6 +26,34 @@ impl<'a> ArgumentsExtractionInput<'a> {
 
 }
 
+///
+/// JSON representation of a value along with the type it is extracted as.
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypedValue {
+
+    value: Value,
+    value_type: ValueType,
+
+    #[serde(default)]
+    extraction_policy: ValueExtractionPolicy
+
+}
+
+impl TypedValue {
+
+    pub fn new(value: Value,
+               value_type: ValueType,
+               extraction_policy: ValueExtractionPolicy) -> TypedValue {
+        TypedValue {
+            value,
+            value_type,
+            extraction_policy
+        }
+    }
+
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ArgumentValueExtractorError {
 
@@ -48,6 +76,23 @@ impl ArgumentValuesExtractionService {
         };
     }
 
+    pub fn process_typed(values: &HashMap<String, TypedValue>)
+                         -> Result<ValuesPayload, ArgumentValueExtractorError> {
+        let mut response: HashMap<String, ValueHolder> = HashMap::new();
+        for (name, typed_value) in values.iter() {
+            let holder = ValueExtractorService::extract(
+                &ValueExtractorInput::new(
+                    &typed_value.value,
+                    &typed_value.value_type,
+                    &typed_value.extraction_policy))
+                .map_err(|error| ArgumentValueExtractorError::ExtractionFailure(
+                    name.clone(), typed_value.value.clone(), error))?;
+
+            response.insert(name.clone(), holder);
+        }
+        Result::Ok(ValuesPayload::new(response))
+    }
+
     fn do_process(payload: &Map<String, Value>,
                   definitions: &HashMap<String, ArgumentDefinition>)
                   -> Result<ValuesPayload, ArgumentValueExtractorError> {
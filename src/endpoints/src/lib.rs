@@ -10,15 +10,52 @@ use crate::extraction::{ArgumentsExtractionInput, ArgumentValueExtractorError, A
 pub mod endpoints;
 pub mod extraction;
 
+///
+/// Named set of arguments accepted together, under the name of the set.
+///
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ArgumentSetDefinition {
 
     id: i32,
     name: String,
-    description: String
+    description: String,
+    arguments: ArgumentsExtractor
 
 }
 
-#[derive(Serialize, Deserialize)]
+impl ArgumentSetDefinition {
+
+    pub fn new(id: i32,
+               name: String,
+               description: String,
+               arguments: ArgumentsExtractor) -> ArgumentSetDefinition {
+        ArgumentSetDefinition {
+            id,
+            name,
+            description,
+            arguments
+        }
+    }
+
+    pub fn get_id(&self) -> &i32 {
+        &self.id
+    }
+
+    pub fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn get_description(&self) -> &String {
+        &self.description
+    }
+
+    pub fn extract(&self, payload: &Value) -> Result<ValuesPayload, ArgumentValueExtractorError> {
+        self.arguments.extract(payload)
+    }
+
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ArgumentDefinition {
 
     id: i32,
@@ -63,7 +100,10 @@ impl ArgumentDefinition {
 
 }
 
-#[derive(Serialize, Deserialize)]
+///
+/// Extracts typed values of the defined arguments, all of them are required.
+///
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct ArgumentsExtractor {
 
     argument_definitions: HashMap<String, ArgumentDefinition>
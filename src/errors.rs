@@ -0,0 +1,325 @@
+use std::fmt::{Display, Formatter};
+
+use actix_web::{HttpResponse, ResponseError, web};
+use actix_web::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use buttercup_agents::scheduling::SchedulingError;
+use buttercup_agents::service::AgentServiceError;
+use buttercup_api::bts::BehaviorTreeBuildingError;
+use buttercup_api::bts::management::BehaviorTreeManagementError;
+use buttercup_blackboards::LocalBlackboardError;
+use buttercup_bts::context::BTNodeContextServiceError;
+use buttercup_bts::context::debug::DebugContextError;
+use buttercup_bts::context::recording::RecordingContextError;
+use buttercup_endpoints::endpoints::EndpointError;
+
+///
+/// Body of every error response. The code is the name of the error, the details
+/// hold the error itself.
+///
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ErrorResponse {
+
+    code: String,
+    message: String,
+    details: Value
+
+}
+
+#[derive(Debug)]
+pub struct ApiError {
+
+    status: StatusCode,
+    response: ErrorResponse
+
+}
+
+impl ErrorResponse {
+
+    pub fn get_code(&self) -> &String {
+        &self.code
+    }
+
+    pub fn get_message(&self) -> &String {
+        &self.message
+    }
+
+    pub fn get_details(&self) -> &Value {
+        &self.details
+    }
+
+}
+
+impl ApiError {
+
+    pub fn new(status: StatusCode,
+               code: String,
+               message: String,
+               details: Value) -> ApiError {
+        ApiError {
+            status,
+            response: ErrorResponse {
+                code,
+                message,
+                details
+            }
+        }
+    }
+
+    pub fn bad_request(message: String) -> ApiError {
+        ApiError::new(StatusCode::BAD_REQUEST, "BadRequest".to_owned(), message, Value::Null)
+    }
+
+    pub fn not_found(message: String) -> ApiError {
+        ApiError::new(StatusCode::NOT_FOUND, "NotFound".to_owned(), message, Value::Null)
+    }
+
+    fn from_error<E: Serialize>(status: StatusCode,
+                                message: &str,
+                                err: &E) -> ApiError {
+        let details = serde_json::to_value(err).unwrap_or(Value::Null);
+
+        ApiError::new(status, get_code(&details), message.to_owned(), details)
+    }
+
+    pub fn get_status(&self) -> &StatusCode {
+        &self.status
+    }
+
+    pub fn get_response(&self) -> &ErrorResponse {
+        &self.response
+    }
+
+}
+
+///
+/// Extractor configurations answering malformed bodies, paths and queries with the error body.
+///
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default()
+        .error_handler(|err, _| ApiError::bad_request(err.to_string()).into())
+}
+
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default()
+        .error_handler(|err, _| ApiError::bad_request(err.to_string()).into())
+}
+
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default()
+        .error_handler(|err, _| ApiError::bad_request(err.to_string()).into())
+}
+
+///
+/// Errors are serialized as either the name of the variant, or an object keyed by it.
+///
+fn get_code(details: &Value) -> String {
+    match details {
+        Value::String(name) => name.clone(),
+        Value::Object(fields) if fields.len() == 1 =>
+            fields.keys().next().cloned().unwrap_or_default(),
+        _ => "Error".to_owned()
+    }
+}
+
+fn describe_agent_service_error(err: &AgentServiceError) -> (StatusCode, &'static str) {
+    match err {
+        AgentServiceError::AgentAlreadyStarted =>
+            (StatusCode::CONFLICT, "Agent is already running."),
+        AgentServiceError::AgentNotRunning =>
+            (StatusCode::CONFLICT, "Agent is not running."),
+        AgentServiceError::AgentOfGivenIdNotFound =>
+            (StatusCode::NOT_FOUND, "Agent not found."),
+        AgentServiceError::BlackboardError(err) => describe_blackboard_error(err),
+        AgentServiceError::BTNodeContextServiceError(
+            BTNodeContextServiceError::LocalBlackboardError(err)) => describe_blackboard_error(err),
+        AgentServiceError::DebugContextError(DebugContextError::DebuggingNotEnabled) =>
+            (StatusCode::CONFLICT, "Debugging of the agent is not enabled."),
+        AgentServiceError::DebugContextError(DebugContextError::NoPausedNodes) =>
+            (StatusCode::CONFLICT, "Agent has no paused nodes."),
+        AgentServiceError::EndpointError(err) => describe_endpoint_error(err),
+        AgentServiceError::EndpointOfGivenNameNotFound(_) =>
+            (StatusCode::NOT_FOUND, "Endpoint not found."),
+        AgentServiceError::InvalidArguments(_) =>
+            (StatusCode::UNPROCESSABLE_ENTITY, "Invalid arguments."),
+        AgentServiceError::IOError(_) =>
+            (StatusCode::INTERNAL_SERVER_ERROR, "I/O error."),
+        AgentServiceError::RecordingContextError(RecordingContextError::LockPoisonedError) =>
+            (StatusCode::INTERNAL_SERVER_ERROR, "Recording is not accessible."),
+        AgentServiceError::RecordingContextError(RecordingContextError::RecordingNotStarted) =>
+            (StatusCode::CONFLICT, "Recording is not started."),
+        AgentServiceError::RecordingContextError(RecordingContextError::ReplayInProgress) =>
+            (StatusCode::CONFLICT, "Replay is in progress."),
+        AgentServiceError::ScheduleOfGivenIdNotFound =>
+            (StatusCode::NOT_FOUND, "Schedule not found."),
+        AgentServiceError::SchedulingError(SchedulingError::InvalidCronExpression(_))
+        | AgentServiceError::SchedulingError(SchedulingError::NonExistentLocalTime(_))
+        | AgentServiceError::SchedulingError(SchedulingError::StartInPast) =>
+            (StatusCode::UNPROCESSABLE_ENTITY, "Invalid schedule."),
+        AgentServiceError::SchedulingError(_) =>
+            (StatusCode::INTERNAL_SERVER_ERROR, "Schedules could not be persisted."),
+        AgentServiceError::TreeOfGivenIdNotFound(_) =>
+            (StatusCode::NOT_FOUND, "Tree not found."),
+        AgentServiceError::TreeVersionNotFound(_, _) =>
+            (StatusCode::NOT_FOUND, "Tree version not found.")
+    }
+}
+
+fn describe_endpoint_error(err: &EndpointError) -> (StatusCode, &'static str) {
+    match err {
+        EndpointError::BlackboardError(err) => describe_blackboard_error(err),
+        EndpointError::LockPoisonedError =>
+            (StatusCode::INTERNAL_SERVER_ERROR, "Endpoint is not accessible.")
+    }
+}
+
+fn describe_blackboard_error(err: &LocalBlackboardError) -> (StatusCode, &'static str) {
+    match err {
+        LocalBlackboardError::BlackboardOfGivenIdNotFound(_) =>
+            (StatusCode::NOT_FOUND, "Blackboard not found."),
+        LocalBlackboardError::BlackboardClosed =>
+            (StatusCode::NOT_FOUND, "Blackboard has been destroyed."),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "Blackboard error.")
+    }
+}
+
+fn describe_tree_management_error(err: &BehaviorTreeManagementError) -> (StatusCode, &'static str) {
+    match err {
+        BehaviorTreeManagementError::BuildingError(_) =>
+            (StatusCode::UNPROCESSABLE_ENTITY, "Tree could not be built."),
+        BehaviorTreeManagementError::CyclicSubtreeReference(_) =>
+            (StatusCode::UNPROCESSABLE_ENTITY, "Tree references itself as a subtree."),
+        BehaviorTreeManagementError::IOError(_)
+        | BehaviorTreeManagementError::SerializationError(_) =>
+            (StatusCode::INTERNAL_SERVER_ERROR, "Trees could not be persisted."),
+        BehaviorTreeManagementError::MismatchedTreeId(_) =>
+            (StatusCode::UNPROCESSABLE_ENTITY, "Tree id does not match the one of the path."),
+        BehaviorTreeManagementError::TreeIsUsedAsSubtree(_) =>
+            (StatusCode::CONFLICT, "Tree is used as a subtree."),
+        BehaviorTreeManagementError::TreeOfGivenIdAlreadyExists(_) =>
+            (StatusCode::CONFLICT, "Tree already exists."),
+        BehaviorTreeManagementError::TreeOfGivenIdNotFound(_) =>
+            (StatusCode::NOT_FOUND, "Tree not found."),
+        BehaviorTreeManagementError::TreeVersionIsPinned(_, _) =>
+            (StatusCode::CONFLICT, "Tree version is used by agents.")
+    }
+}
+
+impl From<AgentServiceError> for ApiError {
+    fn from(err: AgentServiceError) -> Self {
+        let (status, message) = describe_agent_service_error(&err);
+
+        ApiError::from_error(status, message, &err)
+    }
+}
+
+impl From<EndpointError> for ApiError {
+    fn from(err: EndpointError) -> Self {
+        let (status, message) = describe_endpoint_error(&err);
+
+        ApiError::from_error(status, message, &err)
+    }
+}
+
+impl From<LocalBlackboardError> for ApiError {
+    fn from(err: LocalBlackboardError) -> Self {
+        let (status, message) = describe_blackboard_error(&err);
+
+        ApiError::from_error(status, message, &err)
+    }
+}
+
+impl From<BehaviorTreeBuildingError> for ApiError {
+    fn from(err: BehaviorTreeBuildingError) -> Self {
+        ApiError::from_error(StatusCode::UNPROCESSABLE_ENTITY, "Tree could not be built.", &err)
+    }
+}
+
+impl From<BehaviorTreeManagementError> for ApiError {
+    fn from(err: BehaviorTreeManagementError) -> Self {
+        let (status, message) = describe_tree_management_error(&err);
+
+        ApiError::from_error(status, message, &err)
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.response.message)
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status).json(&self.response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{App, test};
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_maps_errors_to_status_codes_and_codes() {
+        let not_found: ApiError = AgentServiceError::AgentOfGivenIdNotFound.into();
+
+        assert_eq!(StatusCode::NOT_FOUND, *not_found.get_status());
+        assert_eq!("AgentOfGivenIdNotFound", not_found.get_response().get_code());
+        assert_eq!(Value::String("AgentOfGivenIdNotFound".to_owned()),
+                   *not_found.get_response().get_details());
+
+        let blackboard_id = Uuid::new_v4();
+        let nested: ApiError = AgentServiceError::EndpointError(
+            EndpointError::BlackboardError(
+                LocalBlackboardError::BlackboardOfGivenIdNotFound(blackboard_id))).into();
+
+        assert_eq!(StatusCode::NOT_FOUND, *nested.get_status());
+        assert_eq!("EndpointError", nested.get_response().get_code());
+
+        let conflict: ApiError = BehaviorTreeManagementError::TreeOfGivenIdAlreadyExists(1).into();
+
+        assert_eq!(StatusCode::CONFLICT, *conflict.get_status());
+        assert_eq!("TreeOfGivenIdAlreadyExists", conflict.get_response().get_code());
+
+        let invalid: ApiError = AgentServiceError::InvalidArguments("recipient".to_owned()).into();
+
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, *invalid.get_status());
+
+        let internal: ApiError = LocalBlackboardError::DbError("closed".to_owned()).into();
+
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, *internal.get_status());
+        assert_eq!("DbError", internal.get_response().get_code());
+    }
+
+    #[test]
+    fn test_answers_malformed_path_with_error_body() {
+        actix_web::rt::System::new("test").block_on(async {
+            let mut app = test::init_service(
+                App::new()
+                    .app_data(path_config())
+                    .route("/agents/{agent_id}",
+                           web::get().to(|agent_id: web::Path<Uuid>| async move {
+                               Result::<_, ApiError>::Ok(HttpResponse::Ok().body(agent_id.to_string()))
+                           }))).await;
+
+            let response = test::call_service(
+                &mut app, test::TestRequest::get().uri("/agents/not-a-uuid").to_request()).await;
+
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+
+            let body: ErrorResponse = test::read_body_json(response).await;
+
+            assert_eq!("BadRequest", body.get_code());
+        });
+    }
+
+}
@@ -1,66 +1,421 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use actix::{Actor, Addr, Arbiter};
-use actix_web::{App, http, HttpRequest, HttpServer, middleware};
-use actix_web::{post, Responder, web};
-use actix_web::web::{Data, get, resource};
+use actix_web::{App, http, HttpRequest, HttpResponse, HttpServer, middleware};
+use actix_web::{delete, get, patch, post, put, web};
+use actix_web::web::{Bytes, Data, resource};
+use chrono::NaiveDateTime;
 use dashmap::DashMap;
 use env_logger;
+use futures::StreamExt;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use uuid::Uuid;
 
+use buttercup_agents::{AgentDefinition, AgentState, ReactiveErrorPolicy};
+use buttercup_agents::listing::{AgentFilter, DEFAULT_PAGE_SIZE, Pagination};
+use buttercup_agents::scheduling::{ScheduleDefinition, ScheduleStore};
+use buttercup_agents::supervision::RestartPolicy;
 use buttercup_agents::service::AgentService;
+use buttercup_api::bts::BehaviorTreeDefinitionService;
+use buttercup_api::bts::management::{BehaviorTreeManagementService, BehaviorTreeStore};
+use buttercup_api::bts::serialized::SerializedBehaviorTreeDefinition;
 use buttercup_blackboards::LocalBlackboardService;
 use buttercup_bts::context::{BTNodeContextService, BTNodeExecutionContextHolder};
+use buttercup_bts::context::recording::ExecutionRecording;
+use buttercup_bts::tree::BehaviorTreeService;
 use buttercup_endpoints::endpoints::EndpointService;
-use buttercup_values::ValuesPayload;
+use buttercup_endpoints::extraction::TypedValue;
+use buttercup_values::{ValueHolder, ValuesPayload};
 
+use crate::errors::ApiError;
+
+pub mod errors;
 pub mod test_utils;
 
 
-#[post("/values/{name}/{value}")]
-async fn add_variable_value(
-    data: Data<Arc<EndpointService>>,
-    web::Path((name, value)): web::Path<(String, String)>) -> impl Responder {
+#[derive(Serialize, Deserialize)]
+struct TreeId {
+
+    id: i32,
+
+    #[serde(default)]
+    arguments: Option<Value>,
+
+    #[serde(default)]
+    labels: HashMap<String, String>,
+
+    #[serde(default)]
+    reactive_error_policy: ReactiveErrorPolicy,
+
+    #[serde(default)]
+    restart_policy: RestartPolicy,
+
+    #[serde(default)]
+    tree_version: Option<u32>
 
-    serde_json::to_string(
-        &data.accept_value_changes(&Uuid::from_u128(1),
-                                   ValuesPayload::singleton(name, value.into())))
-        .unwrap()
 }
 
+///
+/// Version of the tree to migrate the agent to, the latest one if none is given.
+///
 #[derive(Serialize, Deserialize)]
-struct TreeId {
+struct Migration {
+
+    #[serde(default)]
+    tree_version: Option<u32>
+
+}
+
+///
+/// Labels are given as comma separated `key:value` pairs.
+///
+#[derive(Serialize, Deserialize)]
+struct AgentsQuery {
 
-    id: i32
+    created_after_utc: Option<NaiveDateTime>,
+    created_before_utc: Option<NaiveDateTime>,
+    labels: Option<String>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    state: Option<AgentState>,
+    tree_id: Option<i32>
 
 }
 
+impl AgentsQuery {
+
+    fn parse_labels(&self) -> Result<HashMap<String, String>, String> {
+        let mut labels = HashMap::new();
+
+        for pair in self.labels.iter().flat_map(|labels| labels.split(',')) {
+            match pair.split_once(':') {
+                Some((key, value)) => {
+                    labels.insert(key.to_owned(), value.to_owned());
+                },
+                None => return Result::Err(format!("Invalid label: {}", pair))
+            }
+        }
+
+        Result::Ok(labels)
+    }
+
+}
+
+#[get("/agents")]
+async fn list_agents(agent_service: Data<Arc<AgentService>>,
+                     query: web::Query<AgentsQuery>) -> Result<HttpResponse, ApiError> {
+    let query = query.0;
+    let labels = query.parse_labels().map_err(ApiError::bad_request)?;
+
+    Result::Ok(
+        HttpResponse::Ok().json(
+            agent_service.list_agents(
+                &AgentFilter::new(query.created_after_utc,
+                                  query.created_before_utc,
+                                  labels,
+                                  query.state,
+                                  query.tree_id),
+                &Pagination::new(query.offset.unwrap_or(0),
+                                 query.limit.unwrap_or(DEFAULT_PAGE_SIZE)))))
+}
+
 #[post("/agents")]
 async fn build_new_agent(agent_service: Data<Arc<AgentService>>,
-                         tree_id: web::Json<TreeId>) -> impl Responder {
-    format!("{:?}", agent_service
-        .build_new_agent(&tree_id.0.id)
-        .map(|id| id.to_string()))
+                         tree_id: web::Json<TreeId>) -> Result<HttpResponse, ApiError> {
+    let agent_id = agent_service
+        .build_new_agent(AgentDefinition::new(tree_id.0.arguments,
+                                              tree_id.0.labels,
+                                              tree_id.0.reactive_error_policy,
+                                              tree_id.0.restart_policy,
+                                              tree_id.0.id,
+                                              tree_id.0.tree_version))?;
+
+    Result::Ok(HttpResponse::Created().json(json!({"id": agent_id})))
 }
 
 #[post("/agents/{agent_id}/start")]
 async fn start_agent(agent_service: Data<Arc<AgentService>>,
-                     agent_id: web::Path<Uuid>) -> impl Responder {
-    format!("{:?}", agent_service
-        .start_agent_by_id(&agent_id.0)
-    )
+                     agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    agent_service.start_agent_by_id(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
 }
 
 #[post("/agents/{agent_id}/stop")]
 async fn stop_agent(agent_service: Data<Arc<AgentService>>,
-                    agent_id: web::Path<Uuid>) -> impl Responder {
-    format!("{:?}", agent_service
-        .stop_agent_by_id(&agent_id.0)
-    )
+                    agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    agent_service.stop_agent_by_id(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/agents/{agent_id}/migrate")]
+async fn migrate_agent(agent_service: Data<Arc<AgentService>>,
+                       agent_id: web::Path<Uuid>,
+                       migration: web::Json<Migration>) -> Result<HttpResponse, ApiError> {
+    let tree_version = agent_service.migrate_agent(&agent_id.0, &migration.0.tree_version)?;
+
+    Result::Ok(HttpResponse::Ok().json(json!({"tree_version": tree_version})))
+}
+
+#[get("/agents/{agent_id}")]
+async fn get_agent(agent_service: Data<Arc<AgentService>>,
+                   agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    let status = agent_service.get_agent_status(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::Ok().json(status))
+}
+
+#[delete("/agents/{agent_id}")]
+async fn delete_agent(agent_service: Data<Arc<AgentService>>,
+                      agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    agent_service.delete_agent_by_id(&agent_id.0).await?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/agents/{agent_id}/endpoints/{name}")]
+async fn accept_endpoint_values(agent_service: Data<Arc<AgentService>>,
+                                web::Path((agent_id, name)): web::Path<(Uuid, String)>,
+                                payload: web::Json<Value>) -> Result<HttpResponse, ApiError> {
+    agent_service.accept_endpoint_values(&agent_id, &name, &payload.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[put("/agents/{agent_id}/values")]
+async fn replace_values(agent_service: Data<Arc<AgentService>>,
+                        agent_id: web::Path<Uuid>,
+                        values: web::Json<HashMap<String, TypedValue>>) -> Result<HttpResponse, ApiError> {
+    agent_service.replace_values(&agent_id.0, &values.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[patch("/agents/{agent_id}/values")]
+async fn patch_values(agent_service: Data<Arc<AgentService>>,
+                      agent_id: web::Path<Uuid>,
+                      values: web::Json<HashMap<String, TypedValue>>) -> Result<HttpResponse, ApiError> {
+    agent_service.patch_values(&agent_id.0, &values.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/agents/{agent_id}/values")]
+async fn get_values(agent_service: Data<Arc<AgentService>>,
+                    agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    let values = agent_service.get_values(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::Ok().json(values.get_values()))
+}
+
+#[get("/agents/{agent_id}/values/{name}")]
+async fn get_value(agent_service: Data<Arc<AgentService>>,
+                   web::Path((agent_id, name)): web::Path<(Uuid, String)>) -> Result<HttpResponse, ApiError> {
+    match agent_service.get_value(&agent_id, &name)? {
+        Some(value) => Result::Ok(HttpResponse::Ok().json(value)),
+        None => Result::Err(ApiError::not_found(format!("Value {} not found.", name)))
+    }
+}
+
+#[post("/agents/{agent_id}/schedules")]
+async fn schedule_agent(agent_service: Data<Arc<AgentService>>,
+                        agent_id: web::Path<Uuid>,
+                        definition: web::Json<ScheduleDefinition>) -> Result<HttpResponse, ApiError> {
+    let schedule_id = agent_service.schedule_agent(&agent_id.0, definition.0)?;
+
+    Result::Ok(HttpResponse::Created().json(json!({"id": schedule_id})))
+}
+
+#[get("/agents/{agent_id}/schedules")]
+async fn get_schedules(agent_service: Data<Arc<AgentService>>,
+                       agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    let schedules = agent_service.get_schedules(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::Ok().json(schedules))
+}
+
+#[delete("/agents/{agent_id}/schedules/{schedule_id}")]
+async fn delete_schedule(agent_service: Data<Arc<AgentService>>,
+                         web::Path((agent_id, schedule_id)): web::Path<(Uuid, Uuid)>) -> Result<HttpResponse, ApiError> {
+    agent_service.delete_schedule(&agent_id, &schedule_id)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/agents/{agent_id}/events")]
+async fn stream_agent_events(agent_service: Data<Arc<AgentService>>,
+                             agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    let events = agent_service.subscribe_to_events(&agent_id.0)?;
+
+    Result::Ok(
+        HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .streaming(
+                events.map(|event|
+                    Result::Ok::<Bytes, actix_web::Error>(
+                        Bytes::from(format!("data: {}\n\n", event))))))
 }
 
+#[derive(Serialize, Deserialize)]
+struct Breakpoints {
+
+    breakpoints: HashSet<i32>
+
+}
+
+#[post("/agents/{agent_id}/debug")]
+async fn enable_debugging(agent_service: Data<Arc<AgentService>>,
+                          agent_id: web::Path<Uuid>,
+                          breakpoints: web::Json<Breakpoints>) -> Result<HttpResponse, ApiError> {
+    agent_service.enable_debugging(&agent_id.0, breakpoints.0.breakpoints)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[delete("/agents/{agent_id}/debug")]
+async fn disable_debugging(agent_service: Data<Arc<AgentService>>,
+                           agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    agent_service.disable_debugging(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/agents/{agent_id}/debug")]
+async fn get_debug_state(agent_service: Data<Arc<AgentService>>,
+                         agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    let state = agent_service.get_debug_state(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::Ok().json(state))
+}
+
+#[put("/agents/{agent_id}/debug/breakpoints/{node_id}")]
+async fn add_breakpoint(agent_service: Data<Arc<AgentService>>,
+                        web::Path((agent_id, node_id)): web::Path<(Uuid, i32)>) -> Result<HttpResponse, ApiError> {
+    agent_service.add_breakpoint(&agent_id, node_id)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[delete("/agents/{agent_id}/debug/breakpoints/{node_id}")]
+async fn remove_breakpoint(agent_service: Data<Arc<AgentService>>,
+                           web::Path((agent_id, node_id)): web::Path<(Uuid, i32)>) -> Result<HttpResponse, ApiError> {
+    agent_service.remove_breakpoint(&agent_id, &node_id)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/agents/{agent_id}/debug/step")]
+async fn step_agent(agent_service: Data<Arc<AgentService>>,
+                    agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    agent_service.step_agent_by_id(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/agents/{agent_id}/debug/continue")]
+async fn resume_agent(agent_service: Data<Arc<AgentService>>,
+                      agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    agent_service.resume_agent_by_id(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[put("/agents/{agent_id}/debug/values")]
+async fn put_debug_values(agent_service: Data<Arc<AgentService>>,
+                          endpoint_service: Data<Arc<EndpointService>>,
+                          agent_id: web::Path<Uuid>,
+                          values: web::Json<HashMap<String, ValueHolder>>) -> Result<HttpResponse, ApiError> {
+    let blackboard_id = agent_service.get_blackboard_id(&agent_id.0)?;
+
+    endpoint_service.accept_value_changes(&blackboard_id, ValuesPayload::new(values.0))?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/agents/{agent_id}/recording")]
+async fn start_recording(agent_service: Data<Arc<AgentService>>,
+                         agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    agent_service.start_recording(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/agents/{agent_id}/recording")]
+async fn get_recording(agent_service: Data<Arc<AgentService>>,
+                       agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    let recording = agent_service.get_recording(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::Ok().json(recording))
+}
+
+#[delete("/agents/{agent_id}/recording")]
+async fn stop_recording(agent_service: Data<Arc<AgentService>>,
+                        agent_id: web::Path<Uuid>) -> Result<HttpResponse, ApiError> {
+    let recording = agent_service.stop_recording(&agent_id.0)?;
+
+    Result::Ok(HttpResponse::Ok().json(recording))
+}
+
+#[post("/trees")]
+async fn create_tree(tree_management_service: Data<Arc<BehaviorTreeManagementService>>,
+                     definition: web::Json<SerializedBehaviorTreeDefinition>) -> Result<HttpResponse, ApiError> {
+    let tree_id = *definition.0.get_id();
+
+    tree_management_service.create(definition.0)?;
+
+    Result::Ok(HttpResponse::Created().json(json!({"id": tree_id})))
+}
+
+#[get("/trees")]
+async fn list_trees(tree_management_service: Data<Arc<BehaviorTreeManagementService>>) -> HttpResponse {
+    HttpResponse::Ok().json(tree_management_service.list())
+}
+
+#[get("/trees/{tree_id}")]
+async fn get_tree(tree_management_service: Data<Arc<BehaviorTreeManagementService>>,
+                  tree_id: web::Path<i32>) -> Result<HttpResponse, ApiError> {
+    match tree_management_service.get(&tree_id.0) {
+        Some(definition) => Result::Ok(HttpResponse::Ok().json(definition)),
+        None => Result::Err(ApiError::not_found(format!("Tree {} not found.", tree_id.0)))
+    }
+}
+
+#[get("/trees/{tree_id}/versions")]
+async fn get_tree_versions(tree_management_service: Data<Arc<BehaviorTreeManagementService>>,
+                           tree_id: web::Path<i32>) -> HttpResponse {
+    HttpResponse::Ok().json(tree_management_service.get_versions(&tree_id.0))
+}
+
+#[put("/trees/{tree_id}")]
+async fn update_tree(tree_management_service: Data<Arc<BehaviorTreeManagementService>>,
+                     tree_id: web::Path<i32>,
+                     definition: web::Json<SerializedBehaviorTreeDefinition>) -> Result<HttpResponse, ApiError> {
+    tree_management_service.update(&tree_id.0, definition.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[delete("/trees/{tree_id}")]
+async fn delete_tree(tree_management_service: Data<Arc<BehaviorTreeManagementService>>,
+                     tree_id: web::Path<i32>) -> Result<HttpResponse, ApiError> {
+    tree_management_service.delete(&tree_id.0)?;
+
+    Result::Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/trees/{tree_id}/replays")]
+async fn build_replaying_agent(agent_service: Data<Arc<AgentService>>,
+                               tree_id: web::Path<i32>,
+                               recording: web::Json<ExecutionRecording>) -> Result<HttpResponse, ApiError> {
+    let agent_id = agent_service.build_replaying_agent(&tree_id.0, &recording.0)?;
+
+    Result::Ok(HttpResponse::Created().json(json!({"id": agent_id})))
+}
 
 #[actix_rt::main]
 async fn main() -> std::io::Result<()> {
@@ -79,20 +434,79 @@ async fn main() -> std::io::Result<()> {
         Arc::new(BTNodeContextService::new(endpoint_service.clone(),
                                            blackboard_service.clone()));
 
+    let schedules_path = std::env::var("BUTTERCUP_SCHEDULES_PATH")
+        .unwrap_or_else(|_| "schedules.json".to_owned());
+
+    let trees_path = std::env::var("BUTTERCUP_TREES_PATH")
+        .unwrap_or_else(|_| "trees.json".to_owned());
+
+    let tree_service = Arc::new(BehaviorTreeService::default());
+    let tree_management_service = Arc::new(
+        BehaviorTreeManagementService::with_store(tree_service.clone(),
+                                                  Arc::new(BehaviorTreeDefinitionService::default()),
+                                                  BehaviorTreeStore::new(trees_path.into())));
+
     let agent_service =
-        test_utils::build_test_agent_service(context_service.clone());
+        test_utils::build_test_agent_service(context_service.clone(),
+                                             ScheduleStore::new(schedules_path.into()),
+                                             tree_service);
+
+    match tree_management_service.restore() {
+        Ok(restored) => info!("Restored {} trees.", restored),
+        Err(err) => warn!("Trees could not be restored: {:?}", err)
+    }
+
+    match agent_service.restore_schedules() {
+        Ok(restored) => info!("Restored {} schedules.", restored),
+        Err(err) => warn!("Schedules could not be restored: {:?}", err)
+    }
 
     let agent_service_data = Data::new(Arc::new(agent_service));
     let endpoints_service_data = Data::new(endpoint_service);
+    let tree_management_service_data = Data::new(tree_management_service);
 
     HttpServer::new(move || {
         App::new()
+            .app_data(errors::json_config())
+            .app_data(errors::path_config())
+            .app_data(errors::query_config())
             .app_data(endpoints_service_data.clone())
             .app_data(agent_service_data.clone())
-            .service(add_variable_value)
+            .app_data(tree_management_service_data.clone())
             .service(build_new_agent)
+            .service(list_agents)
             .service(start_agent)
             .service(stop_agent)
+            .service(migrate_agent)
+            .service(get_agent)
+            .service(delete_agent)
+            .service(accept_endpoint_values)
+            .service(replace_values)
+            .service(patch_values)
+            .service(get_values)
+            .service(get_value)
+            .service(schedule_agent)
+            .service(get_schedules)
+            .service(delete_schedule)
+            .service(stream_agent_events)
+            .service(enable_debugging)
+            .service(disable_debugging)
+            .service(get_debug_state)
+            .service(add_breakpoint)
+            .service(remove_breakpoint)
+            .service(step_agent)
+            .service(resume_agent)
+            .service(put_debug_values)
+            .service(start_recording)
+            .service(get_recording)
+            .service(stop_recording)
+            .service(create_tree)
+            .service(list_trees)
+            .service(get_tree)
+            .service(get_tree_versions)
+            .service(update_tree)
+            .service(delete_tree)
+            .service(build_replaying_agent)
             .wrap(middleware::Logger::default())
     })
         .bind("127.0.0.1:7777")?.run().await
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use buttercup_agents::scheduling::ScheduleStore;
 use buttercup_agents::service::AgentService;
 use buttercup_bts::context::BTNodeContextService;
 use buttercup_bts::node::action::logging::PrintLogActionNode;
@@ -16,19 +17,19 @@ use buttercup_conditions::{ConditionExpression, ConditionExpressionWrapper, Logi
 use buttercup_conditions::relational::{EndsWithRelationalExpression, StartsWithRelationalExpression};
 use buttercup_endpoints::endpoints::EndpointService;
 
-pub fn build_test_agent_service(context_service: Arc<BTNodeContextService>) -> AgentService {
-    let tree_service = Arc::new(BehaviorTreeService::default());
-
+pub fn build_test_agent_service(context_service: Arc<BTNodeContextService>,
+                                schedule_store: ScheduleStore,
+                                tree_service: Arc<BehaviorTreeService>) -> AgentService {
     add_test_trees(tree_service.as_ref());
 
-    AgentService::new(context_service, tree_service).unwrap()
+    AgentService::new(context_service, schedule_store, tree_service).unwrap()
 }
 
 pub fn add_test_trees(bt_service: &BehaviorTreeService) {
     bt_service.insert(build_one_off_tree());
     bt_service.insert(build_until_stopped_tree());
     bt_service.insert(build_to_first_fail_tree());
-    bt_service.insert(build_tree_with_subtree(bt_service.get_by_id(&1).unwrap()))
+    bt_service.insert(build_tree_with_subtree(bt_service.get_by_id(&1).unwrap()));
 }
 
 
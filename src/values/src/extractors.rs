@@ -31,9 +31,10 @@ pub(crate) mod lists;
 pub(crate) mod number;
 pub(crate) mod string;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub enum ValueExtractionPolicy {
 
+    #[default]
     Strict,
     Lax
 
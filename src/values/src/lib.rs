@@ -10,6 +10,7 @@ use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use isocountry::CountryCode;
 use num::bigint::BigInt;
 use num::rational::BigRational;
+use num::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, EnumIter, EnumVariantNames};
@@ -104,6 +105,40 @@ impl TryFrom<ValueHolder> for Duration {
     }
 }
 
+impl TryFrom<ValueHolder> for BigInt {
+    type Error = ();
+
+    fn try_from(value: ValueHolder) -> Result<Self, Self::Error> {
+        match value {
+            ValueHolder::Integer(integer) => Result::Ok(integer),
+            _ => Result::Err(())
+        }
+    }
+}
+
+impl TryFrom<ValueHolder> for BigRational {
+    type Error = ();
+
+    fn try_from(value: ValueHolder) -> Result<Self, Self::Error> {
+        match value {
+            ValueHolder::Decimal(decimal) => Result::Ok(decimal),
+            ValueHolder::Integer(integer) => Result::Ok(BigRational::from_integer(integer)),
+            _ => Result::Err(())
+        }
+    }
+}
+
+impl TryFrom<ValueHolder> for i64 {
+    type Error = ();
+
+    fn try_from(value: ValueHolder) -> Result<Self, Self::Error> {
+        match value {
+            ValueHolder::Integer(integer) => integer.to_i64().ok_or(()),
+            _ => Result::Err(())
+        }
+    }
+}
+
 impl From<String> for ValueHolder {
     fn from(val: String) -> Self {
         ValueHolder::String(Arc::new(val))
@@ -181,6 +216,21 @@ impl ValuesPayload {
         ValuesPayload::new(values)
     }
 
+    ///
+    /// Payload of a replacement, the removed names are among its keys but have no values.
+    ///
+    pub fn with_removed(values: HashMap<String, ValueHolder>,
+                        removed_names: HashSet<String>) -> ValuesPayload {
+        let mut keys: HashSet<String> = values.keys().cloned().collect();
+
+        keys.extend(removed_names);
+
+        ValuesPayload {
+            values,
+            keys
+        }
+    }
+
     pub fn empty() -> ValuesPayload {
         ValuesPayload {
             values: HashMap::new(),
@@ -205,6 +255,10 @@ impl ValuesPayload {
         self.keys
     }
 
+    pub fn into_values(self) -> HashMap<String, ValueHolder> {
+        self.values
+    }
+
 }
 
 
@@ -222,6 +276,20 @@ mod tests {
         assert_eq!(ValueHolder::VARIANTS, ValueType::VARIANTS);
     }
 
+    #[test]
+    fn test_converts_integers_within_range() {
+        assert_eq!(Ok(42), i64::try_from(ValueHolder::Integer(BigInt::from(42))));
+        assert_eq!(Err(()), i64::try_from(ValueHolder::Integer(BigInt::from(i128::MAX))));
+        assert_eq!(Err(()), i64::try_from(ValueHolder::Boolean(true)));
+    }
+
+    #[test]
+    fn test_converts_integers_to_decimals() {
+        assert_eq!(Ok(BigRational::from_integer(BigInt::from(2))),
+                   BigRational::try_from(ValueHolder::Integer(BigInt::from(2))));
+        assert_eq!(Err(()), BigInt::try_from(ValueHolder::Decimal(BigRational::from_f64(0.5).unwrap())));
+    }
+
     #[test]
     fn test_ne() {
         assert_ne!(ValueHolder::Decimal(BigRational::from_f64(0.321421).unwrap()),
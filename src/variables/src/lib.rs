@@ -41,7 +41,7 @@ pub trait VariableService {
 }
 
 #[derive(Serialize, Deserialize, Eq, Hash, PartialEq, PartialOrd, Clone)]
-pub enum VariableSpecification<T: TryFrom<ValueHolder> + Copy> {
+pub enum VariableSpecification<T: TryFrom<ValueHolder>> {
 
     Literal(Arc<T>),
     VariableName(VariableName)
@@ -77,19 +77,19 @@ pub enum VariableValueAccessError {
 
 }
 
-impl<T: TryFrom<ValueHolder> + Copy> From<T> for VariableSpecification<T> {
+impl<T: TryFrom<ValueHolder>> From<T> for VariableSpecification<T> {
     fn from(value: T) -> Self {
         VariableSpecification::Literal(Arc::new(value))
     }
 }
 
-impl<T: TryFrom<ValueHolder> + Copy> From<VariableName> for VariableSpecification<T> {
+impl<T: TryFrom<ValueHolder>> From<VariableName> for VariableSpecification<T> {
     fn from(variable_name: VariableName) -> Self {
         VariableSpecification::VariableName(variable_name)
     }
 }
 
-impl<T: TryFrom<ValueHolder> + Copy> VariableSpecification<T> {
+impl<T: TryFrom<ValueHolder>> VariableSpecification<T> {
 
     pub fn get_value<S: VariableService>(&self,
                                          service: &S)